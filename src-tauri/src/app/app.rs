@@ -1,18 +1,24 @@
+use crate::app::downloads::{DownloadManager, MAX_CONCURRENT_DOWNLOADS};
 use crate::app::settings::Settings;
+use crate::mod_links::remote::RemoteModLinks;
 use threadpool::ThreadPool;
 
 pub struct App {
-    pub current_download_progress: u8,
     pub settings: Settings,
     pub pool: ThreadPool,
+    /// The queue that downloads of mods wait in for their turn to run
+    pub downloads: DownloadManager,
+    /// The mod links most recently fetched from ModLinks.xml
+    pub remote_mod_links: RemoteModLinks,
 }
 
 impl Default for App {
     fn default() -> Self {
         App {
-            current_download_progress: 0,
             settings: Settings::default(),
-            pool: ThreadPool::new(num_cpus::get()),
+            pool: ThreadPool::new(MAX_CONCURRENT_DOWNLOADS),
+            downloads: DownloadManager::default(),
+            remote_mod_links: RemoteModLinks::new(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,225 @@
+use crate::error::Error;
+use serde::Serialize;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// The event that changes to the download queue are emitted to the frontend as
+pub const QUEUE_EVENT: &str = "download-queue";
+
+/// The most downloads that run at the same time
+pub const MAX_CONCURRENT_DOWNLOADS: usize = 3;
+
+/// Whether a download in the queue is waiting or running
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum DownloadStatus {
+    Queued,
+    Active,
+}
+
+/// A download in the queue
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct QueuedDownload {
+    /// The ID of the operation the download belongs to, as reported with its progress
+    #[serde(rename = "Operation")]
+    pub operation_id: u64,
+    #[serde(rename = "Mod")]
+    pub mod_name: String,
+    /// The expected SHA256 hash of the download, which names its staging file
+    #[serde(skip)]
+    pub sha256: String,
+    #[serde(rename = "Status")]
+    pub status: DownloadStatus,
+    /// Whether the download has been cancelled but has not stopped yet
+    #[serde(rename = "Cancelled")]
+    pub cancelled: bool,
+}
+
+/// The downloads in the queue, in the order they were queued, and whether it is paused
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DownloadQueue {
+    #[serde(rename = "Downloads")]
+    pub downloads: Vec<QueuedDownload>,
+    /// Whether queued downloads are held back and running downloads are halted
+    #[serde(rename = "Paused")]
+    pub paused: bool,
+    #[serde(rename = "Max Concurrent")]
+    pub max_concurrent: usize,
+}
+
+/// Limits how many downloads run at once and lets them be cancelled, paused and resumed.
+/// Clones share the same queue.
+#[derive(Clone)]
+pub struct DownloadManager {
+    shared: Arc<(Mutex<DownloadQueue>, Condvar)>,
+}
+
+impl Default for DownloadManager {
+    fn default() -> Self {
+        DownloadManager {
+            shared: Arc::new((
+                Mutex::new(DownloadQueue {
+                    downloads: vec![],
+                    paused: false,
+                    max_concurrent: MAX_CONCURRENT_DOWNLOADS,
+                }),
+                Condvar::new(),
+            )),
+        }
+    }
+}
+
+impl DownloadManager {
+    /// Add a download to the end of the queue. It stays in the queue until the returned
+    /// ticket is dropped.
+    /// # Arguments
+    /// * `operation_id` - The ID of the operation the download belongs to
+    /// * `mod_name` - The name of the mod being downloaded
+    /// * `sha256` - The expected SHA256 hash of the download
+    pub fn enqueue(&self, operation_id: u64, mod_name: &str, sha256: &str) -> DownloadTicket {
+        self.lock().downloads.push(QueuedDownload {
+            operation_id,
+            mod_name: mod_name.to_string(),
+            sha256: sha256.to_lowercase(),
+            status: DownloadStatus::Queued,
+            cancelled: false,
+        });
+        DownloadTicket {
+            operation_id,
+            manager: self.clone(),
+        }
+    }
+
+    /// Get the downloads in the queue
+    pub fn queue(&self) -> DownloadQueue {
+        self.lock().clone()
+    }
+
+    /// Cancel a queued or running download
+    /// # Arguments
+    /// * `operation_id` - The ID of the operation the download belongs to
+    pub fn cancel(&self, operation_id: u64) -> Result<(), Error> {
+        let mut queue = self.lock();
+        let download = queue
+            .downloads
+            .iter_mut()
+            .find(|d| d.operation_id == operation_id)
+            .ok_or_else(|| {
+                Error::InvalidState(format!(
+                    "Operation {} is not in the download queue.",
+                    operation_id
+                ))
+            })?;
+        download.cancelled = true;
+        self.shared.1.notify_all();
+        Ok(())
+    }
+
+    /// Pause or resume the whole queue
+    /// # Arguments
+    /// * `paused` - Whether the queue should be paused
+    pub fn set_paused(&self, paused: bool) {
+        self.lock().paused = paused;
+        self.shared.1.notify_all();
+    }
+
+    /// Lock the queue
+    fn lock(&self) -> MutexGuard<'_, DownloadQueue> {
+        self.shared.0.lock().unwrap()
+    }
+}
+
+/// A download's place in the queue, which it gives up when dropped
+pub struct DownloadTicket {
+    operation_id: u64,
+    manager: DownloadManager,
+}
+
+impl DownloadTicket {
+    /// Block until the download may start, which is once the queue is not paused, fewer than
+    /// the most concurrent downloads are running and every download queued before it has
+    /// started. A download of the same file as a running download waits for it to finish
+    /// without holding up the rest of the queue, since both would write the same staging file.
+    /// Fails if the download is cancelled while it waits.
+    pub fn wait_for_turn(&self) -> Result<(), Error> {
+        let (lock, condvar) = &*self.manager.shared;
+        let mut queue = lock.lock().unwrap();
+        loop {
+            let active = queue
+                .downloads
+                .iter()
+                .filter(|d| d.status == DownloadStatus::Active)
+                .count();
+            let staging = |sha256: &str| {
+                queue
+                    .downloads
+                    .iter()
+                    .any(|d| d.status == DownloadStatus::Active && d.sha256 == sha256)
+            };
+            let first_queued = queue
+                .downloads
+                .iter()
+                .find(|d| {
+                    d.status == DownloadStatus::Queued
+                        && !d.cancelled
+                        && !staging(d.sha256.as_str())
+                })
+                .map(|d| d.operation_id);
+            let blocked = queue.paused || active >= queue.max_concurrent;
+            let download = queue
+                .downloads
+                .iter_mut()
+                .find(|d| d.operation_id == self.operation_id)
+                .unwrap();
+            if download.cancelled {
+                return Err(cancelled_error(download));
+            }
+            if !blocked && first_queued == Some(self.operation_id) {
+                download.status = DownloadStatus::Active;
+                condvar.notify_all();
+                return Ok(());
+            }
+            queue = condvar.wait(queue).unwrap();
+        }
+    }
+
+    /// Block while the queue is paused. Fails if the download is cancelled, whether or not
+    /// the queue is paused, so it can be called between chunks of a running download.
+    pub fn checkpoint(&self) -> Result<(), Error> {
+        let (lock, condvar) = &*self.manager.shared;
+        let mut queue = lock.lock().unwrap();
+        loop {
+            let paused = queue.paused;
+            let download = queue
+                .downloads
+                .iter()
+                .find(|d| d.operation_id == self.operation_id)
+                .unwrap();
+            if download.cancelled {
+                return Err(cancelled_error(download));
+            }
+            if !paused {
+                return Ok(());
+            }
+            queue = condvar.wait(queue).unwrap();
+        }
+    }
+}
+
+impl Drop for DownloadTicket {
+    fn drop(&mut self) {
+        let mut queue = self.manager.lock();
+        queue
+            .downloads
+            .retain(|d| d.operation_id != self.operation_id);
+        self.manager.shared.1.notify_all();
+    }
+}
+
+/// Create the error a cancelled download fails with
+/// # Arguments
+/// * `download` - The cancelled download
+fn cancelled_error(download: &QueuedDownload) -> Error {
+    Error::Cancelled(format!(
+        "Download of mod {:?} was cancelled.",
+        download.mod_name
+    ))
+}
@@ -0,0 +1,76 @@
+use crate::error::Error;
+use log::{info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Get the paths of all files directly inside a folder whose names match a predicate
+/// # Arguments
+/// * `dir` - The folder to search
+/// * `matches` - Whether a file with the given name belongs to the set
+pub fn matching_files<F: Fn(&str) -> bool>(dir: &Path, matches: F) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(&matches)
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Replace the matching files in one folder with copies of those in another,
+/// returning the number of files copied
+/// # Arguments
+/// * `from` - The folder to copy the files from
+/// * `to` - The folder whose matching files are replaced
+/// * `matches` - Whether a file with the given name belongs to the set
+pub fn replace_files(from: &Path, to: &Path, matches: fn(&str) -> bool) -> Result<usize, Error> {
+    fs::create_dir_all(to)
+        .map_err(|e| Error::Io(format!("Failed to create {}: {}", to.display(), e)))?;
+
+    // Copy every file before removing any so a failure leaves the destination as it was
+    let sources = matching_files(from, matches);
+    let mut copied = vec![];
+    for source in &sources {
+        let file_name = source.file_name().unwrap();
+        let temp_path = to.join(format!("{}.tmp", file_name.to_string_lossy()));
+        if let Err(e) = fs::copy(source, temp_path.as_path()) {
+            for path in &copied {
+                let _ = fs::remove_file(path);
+            }
+            return Err(Error::Io(format!(
+                "Failed to copy {}: {}",
+                source.display(),
+                e
+            )));
+        }
+        copied.push(temp_path);
+    }
+
+    for existing in matching_files(to, matches) {
+        if let Err(e) = fs::remove_file(existing.as_path()) {
+            warn!("Failed to remove {}: {}", existing.display(), e);
+        }
+    }
+    for temp_path in &copied {
+        let final_path = temp_path.with_extension("");
+        fs::rename(temp_path, final_path.as_path())
+            .map_err(|e| Error::Io(format!("Failed to move {}: {}", final_path.display(), e)))?;
+    }
+
+    info!(
+        "Copied {} files from {} to {}",
+        copied.len(),
+        from.display(),
+        to.display()
+    );
+    Ok(copied.len())
+}
@@ -0,0 +1,42 @@
+use crate::app::file_sets::replace_files;
+use crate::error::Error;
+use std::path::Path;
+
+/// The suffix of the files mods store their global settings in, e.g. Benchwarp.GlobalSettings.json
+pub const GLOBAL_SETTINGS_SUFFIX: &str = ".GlobalSettings.json";
+
+/// Whether a file is a mod's global settings file
+/// # Arguments
+/// * `file_name` - The name of the file
+fn is_global_settings(file_name: &str) -> bool {
+    file_name.ends_with(GLOBAL_SETTINGS_SUFFIX)
+}
+
+/// Replace the global settings files in one folder with copies of those in another,
+/// returning the number of files copied
+/// # Arguments
+/// * `from` - The folder to copy the global settings files from
+/// * `to` - The folder whose global settings files are replaced
+pub fn copy_global_settings(from: &Path, to: &Path) -> Result<usize, Error> {
+    replace_files(from, to, is_global_settings)
+}
+
+/// Stash the global settings currently in the saves folder into one set and restore another
+/// set in their place
+/// # Arguments
+/// * `saves_dir` - The folder the game reads global settings from
+/// * `current_set` - The folder that the current global settings belong to
+/// * `target_set` - The folder holding the global settings to be restored
+pub fn switch_global_settings(
+    saves_dir: &Path,
+    current_set: &Path,
+    target_set: &Path,
+) -> Result<(), Error> {
+    if current_set == target_set {
+        return Ok(());
+    }
+
+    copy_global_settings(saves_dir, current_set)?;
+    copy_global_settings(target_set, saves_dir)?;
+    Ok(())
+}
@@ -1,3 +1,14 @@
+#[allow(clippy::module_inception)]
 pub mod app;
+pub mod clock;
+pub mod downloads;
+pub mod file_sets;
+pub mod global_settings;
 pub mod profile;
-pub mod settings;
\ No newline at end of file
+pub mod progress;
+pub mod save_backups;
+pub mod saves;
+pub mod settings;
+pub mod settings_reset;
+pub mod share_code;
+pub mod sources;
\ No newline at end of file
@@ -1,7 +1,481 @@
+use crate::error::Error;
+use crate::mod_links::local::LocalModManifest;
+use crate::mod_links::remote::RemoteModManifest;
+use crate::mod_links::version::ModVersion;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The version of the format that profiles are exported in
+pub const EXPORT_FORMAT_VERSION: u32 = 2;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Profile {
+    #[serde(alias = "Name")]
     pub name: String,
+    #[serde(alias = "Mods")]
     pub mods: Vec<String>,
+    /// Exact versions of mods that the profile installs instead of the latest version on ModLinks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pins: Vec<ModPin>,
+    /// Whether the profile has its own set of mod global settings rather than the shared set
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub global_settings: bool,
+    /// Whether the profile has its own save files rather than the shared ones
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub saves: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl Profile {
+    /// Get the pinned version of a mod in the profile, if it has one
+    /// # Arguments
+    /// * `mod_name` - The name of the mod
+    pub fn pin(&self, mod_name: &str) -> Option<&ModPin> {
+        self.pins.iter().find(|pin| pin.name == mod_name)
+    }
+
+    /// The names of every mod in the profile, including mods that are only pinned
+    pub fn mod_names(&self) -> Vec<String> {
+        let mut names = self.mods.clone();
+        for pin in &self.pins {
+            if !names.contains(&pin.name) {
+                names.push(pin.name.clone());
+            }
+        }
+
+        names
+    }
+
+    /// Compare the mods of this profile with those of another profile
+    /// # Arguments
+    /// * `other` - The profile to compare against
+    pub fn diff(&self, other: &Profile) -> ProfileDiff {
+        let names = self.mod_names();
+        let other_names = other.mod_names();
+        let mut diff = ProfileDiff::default();
+        for name in &names {
+            if !other_names.contains(name) {
+                diff.only_in_first.push(name.clone());
+                continue;
+            }
+
+            let first = self.pin(name.as_str());
+            let second = other.pin(name.as_str());
+            if first != second {
+                diff.differing_pins.push(PinDifference {
+                    name: name.clone(),
+                    first: first.cloned(),
+                    second: second.cloned(),
+                });
+            }
+        }
+        diff.only_in_second = other_names
+            .into_iter()
+            .filter(|name| !names.contains(name))
+            .collect();
+
+        diff
+    }
+
+    /// Add the mods of another profile to this one. Where both profiles pin the same mod,
+    /// this profile's pin is kept.
+    /// # Arguments
+    /// * `other` - The profile to be merged into this one
+    pub fn merge(&mut self, other: &Profile) {
+        for name in &other.mods {
+            if !self.mods.contains(name) {
+                self.mods.push(name.clone());
+            }
+        }
+        for pin in &other.pins {
+            if self.pin(pin.name.as_str()).is_none() {
+                self.pins.push(pin.clone());
+            }
+        }
+    }
+}
+
+/// The differences between the mods of two profiles
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ProfileDiff {
+    #[serde(rename = "Only In First")]
+    pub only_in_first: Vec<String>,
+    #[serde(rename = "Only In Second")]
+    pub only_in_second: Vec<String>,
+    /// Mods in both profiles that are pinned differently
+    #[serde(rename = "Differing Pins")]
+    pub differing_pins: Vec<PinDifference>,
+}
+
+/// How a mod that is in two profiles is pinned in each of them
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PinDifference {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "First")]
+    pub first: Option<ModPin>,
+    #[serde(rename = "Second")]
+    pub second: Option<ModPin>,
+}
+
+/// A specific version of a mod, along with where to download it from
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ModPin {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Version", default)]
+    pub version: ModVersion,
+    #[serde(rename = "SHA256", default)]
+    pub sha256: String,
+    #[serde(rename = "Link", default)]
+    pub link: String,
+    #[serde(rename = "Source", default)]
+    pub source: String,
+    /// Whether the mod is placed in the Mods folder rather than the Disabled folder
+    #[serde(rename = "Enabled", default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl From<&LocalModManifest> for ModPin {
+    fn from(manifest: &LocalModManifest) -> Self {
+        ModPin {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            sha256: manifest.link.sha256.clone(),
+            link: manifest.link.link.clone(),
+            source: manifest.source.clone(),
+            enabled: true,
+        }
+    }
+}
+
+impl From<&RemoteModManifest> for ModPin {
+    fn from(manifest: &RemoteModManifest) -> Self {
+        ModPin {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            sha256: manifest.link.sha256.clone(),
+            link: manifest.link.link.clone(),
+            source: manifest.source.clone(),
+            enabled: true,
+        }
+    }
+}
+
+/// A profile as it is exported, with every mod it needs pinned to an exact version
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ProfileLock {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Mods")]
+    pub mods: Vec<ModPin>,
+}
+
+impl ProfileLock {
+    /// Convert the locked profile into a profile that enables its enabled mods
+    /// and pins every mod to its locked version
+    pub fn into_profile(self) -> Profile {
+        Profile {
+            name: self.name,
+            mods: self
+                .mods
+                .iter()
+                .filter(|pin| pin.enabled)
+                .map(|pin| pin.name.clone())
+                .collect(),
+            pins: self.mods,
+            global_settings: false,
+            saves: false,
+        }
+    }
+}
+
+/// The contents of an exported profiles file
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ProfilesExport {
+    #[serde(rename = "Format Version")]
+    pub format_version: u32,
+    /// The version of the Modding API the profiles were exported with
+    #[serde(rename = "Api Version", default)]
+    pub api_version: String,
+    #[serde(rename = "Profiles")]
+    pub profiles: Vec<ProfileLock>,
+}
+
+/// Profiles exported before exports were versioned
+#[derive(Deserialize)]
+struct LegacyProfilesExport {
+    #[serde(rename = "Profiles")]
+    profiles: Vec<Profile>,
+}
+
+/// What to do with an imported profile whose name is already taken by an existing profile
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum ProfileCollision {
+    /// Import the profile under a new, unused name
+    Rename,
+    /// Replace the existing profile with the imported one
+    Overwrite,
+    /// Keep the existing profile and discard the imported one
+    Skip,
+}
+
+/// What happened to a profile when it was imported
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum ImportOutcome {
+    Added,
+    Renamed,
+    Overwritten,
+    Skipped,
+}
+
+/// The result of importing a single profile
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImportedProfile {
+    /// The name of the profile in the imported file
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// The name the profile was saved under, or None if it was skipped
+    #[serde(rename = "Saved As")]
+    pub saved_as: Option<String>,
+    #[serde(rename = "Outcome")]
+    pub outcome: ImportOutcome,
+    /// Mods in the profile that are not on the current ModLinks
+    #[serde(rename = "Unknown Mods")]
+    pub unknown_mods: Vec<String>,
+    #[serde(rename = "Warnings")]
+    pub warnings: Vec<String>,
+}
+
+/// Parse the contents of an exported profiles file, in either the current or the legacy format,
+/// along with the version of the Modding API they were exported with, which is empty if unknown
+/// # Arguments
+/// * `text` - The contents of the file
+pub fn parse_profiles(text: &str) -> Result<(Vec<Profile>, String), Error> {
+    let json: Value = serde_json::from_str(text)
+        .map_err(|e| Error::Parse(format!("The file is not valid JSON: {}", e)))?;
+    let (profiles, api_version) = if json.get("Format Version").is_some() {
+        let export: ProfilesExport = serde_json::from_value(json)
+            .map_err(|e| Error::Parse(format!("The file is not a valid profiles export: {}", e)))?;
+        if export.format_version > EXPORT_FORMAT_VERSION {
+            return Err(Error::Parse(format!(
+                "The profiles were exported in format version {}, but only versions up to {} are supported.",
+                export.format_version, EXPORT_FORMAT_VERSION
+            )));
+        }
+        let profiles = export
+            .profiles
+            .into_iter()
+            .map(ProfileLock::into_profile)
+            .collect();
+        (profiles, export.api_version)
+    } else {
+        let export: LegacyProfilesExport = serde_json::from_value(json)
+            .map_err(|e| Error::Parse(format!("The file is not a valid profiles export: {}", e)))?;
+        (export.profiles, "".to_string())
+    };
+
+    let profiles = profiles
+        .into_iter()
+        .map(validate_profile)
+        .collect::<Result<Vec<Profile>, Error>>()?;
+    Ok((profiles, api_version))
+}
+
+/// Check that an imported profile is usable, trimming its names and removing duplicate mods
+/// # Arguments
+/// * `profile` - The imported profile
+pub fn validate_profile(profile: Profile) -> Result<Profile, Error> {
+    let name = profile.name.trim().to_string();
+    if name.is_empty() {
+        return Err(Error::Parse(
+            "A profile in the file has no name.".to_string(),
+        ));
+    }
+
+    let mut mods: Vec<String> = vec![];
+    for mod_name in profile.mods {
+        let mod_name = mod_name.trim().to_string();
+        if mod_name.is_empty() {
+            return Err(Error::Parse(format!(
+                "Profile {:?} contains a mod with no name.",
+                name
+            )));
+        }
+        if !mods.contains(&mod_name) {
+            mods.push(mod_name);
+        }
+    }
+
+    let mut pins: Vec<ModPin> = vec![];
+    for mut pin in profile.pins {
+        pin.name = pin.name.trim().to_string();
+        if pin.name.is_empty() {
+            return Err(Error::Parse(format!(
+                "Profile {:?} pins a mod with no name.",
+                name
+            )));
+        }
+        if pins.iter().any(|p| p.name == pin.name) {
+            return Err(Error::Parse(format!(
+                "Profile {:?} pins mod {:?} more than once.",
+                name, pin.name
+            )));
+        }
+        pins.push(pin);
+    }
+
+    // Data owned by a profile, such as its global settings, is never imported with it
+    Ok(Profile {
+        name,
+        mods,
+        pins,
+        global_settings: false,
+        saves: false,
+    })
+}
+
+/// Find a profile by its name
+/// # Arguments
+/// * `profiles` - The existing profiles
+/// * `name` - The name of the profile
+pub fn find_profile<'a>(profiles: &'a [Profile], name: &str) -> Result<&'a Profile, Error> {
+    profiles
+        .iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| Error::InvalidState(format!("Profile {:?} does not exist.", name)))
+}
+
+/// Check that a name can be given to a new profile, returning the name with surrounding
+/// whitespace removed
+/// # Arguments
+/// * `profiles` - The existing profiles
+/// * `name` - The name to be checked
+pub fn validate_profile_name(profiles: &[Profile], name: &str) -> Result<String, Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::InvalidState(
+            "Profile names cannot be empty.".to_string(),
+        ));
+    }
+    if profiles.iter().any(|p| p.name == name) {
+        return Err(Error::InvalidState(format!(
+            "A profile named {:?} already exists.",
+            name
+        )));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Find a name based on the given one that no existing profile uses, e.g. "Name (2)"
+/// # Arguments
+/// * `profiles` - The existing profiles
+/// * `name` - The preferred name
+pub fn unique_profile_name(profiles: &[Profile], name: &str) -> String {
+    let taken = |candidate: &str| profiles.iter().any(|p| p.name == candidate);
+    if !taken(name) {
+        return name.to_string();
+    }
+
+    let mut number = 2;
+    loop {
+        let candidate = format!("{} ({})", name, number);
+        if !taken(candidate.as_str()) {
+            return candidate;
+        }
+        number += 1;
+    }
+}
+
+/// Get the name of the folder that a profile's data is stored in. Every character other than
+/// a lowercase letter, digit or space is escaped as the percent-encoded bytes of its UTF-8
+/// form, so that no two profile names share a folder, even on file systems that ignore case.
+/// # Arguments
+/// * `name` - The name of the profile
+pub fn profile_folder_name(name: &str) -> String {
+    let mut folder_name = String::new();
+    for c in name.chars() {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() || c == ' ' {
+            folder_name.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                folder_name.push_str(format!("%{:02X}", byte).as_str());
+            }
+        }
+    }
+
+    folder_name
+}
+
+/// A single change made to the Mods folder while applying a profile
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProfileStep {
+    Enable(String),
+    Disable(String),
+    /// Install a missing mod, into the Disabled folder if the pin is not enabled
+    Install(ModPin),
+    /// Replace the installed version of a mod with a pinned version
+    Replace {
+        previous: ModPin,
+        pin: ModPin,
+    },
+}
+
+/// The changes made to the Mods folder when applying a profile
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ProfileChanges {
+    #[serde(rename = "Enabled")]
+    pub enabled: Vec<String>,
+    #[serde(rename = "Disabled")]
+    pub disabled: Vec<String>,
+    #[serde(rename = "Installed")]
+    pub installed: Vec<String>,
+    #[serde(rename = "Replaced")]
+    pub replaced: Vec<String>,
+}
+
+impl ProfileChanges {
+    /// Summarize a list of steps taken while applying a profile
+    /// # Arguments
+    /// * `steps` - The steps that were taken
+    pub fn from_steps(steps: &[ProfileStep]) -> ProfileChanges {
+        let mut changes = ProfileChanges::default();
+        for step in steps {
+            match step {
+                ProfileStep::Enable(name) => changes.enabled.push(name.clone()),
+                ProfileStep::Disable(name) => changes.disabled.push(name.clone()),
+                ProfileStep::Install(pin) => changes.installed.push(pin.name.clone()),
+                ProfileStep::Replace { pin, .. } => changes.replaced.push(pin.name.clone()),
+            }
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_folder_names_are_distinct() {
+        let names = ["A/B", "A?B", "A_B", "a_b", "A%2FB", "ab", "AB"];
+        let folder_names: Vec<String> = names.iter().map(|n| profile_folder_name(n)).collect();
+        for (i, folder_name) in folder_names.iter().enumerate() {
+            assert!(!folder_names[i + 1..]
+                .iter()
+                .any(|other| other.eq_ignore_ascii_case(folder_name)));
+        }
+        assert_eq!(profile_folder_name("my profile 2"), "my profile 2");
+        assert_eq!(profile_folder_name("A/B"), "%41%2F%42");
+    }
 }
@@ -0,0 +1,142 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// The event that the progress of operations is emitted to the frontend as
+pub const PROGRESS_EVENT: &str = "download-progress";
+
+/// The shortest time between two reports of the same phase of an operation, so that
+/// fast downloads do not flood the frontend with events
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The ID given to the next operation that is tracked
+static NEXT_OPERATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The step an operation on a mod is at
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum ProgressPhase {
+    Queued,
+    Download,
+    Verify,
+    Extract,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+/// A report of how far an operation on a mod has come
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DownloadProgress {
+    /// The ID of the operation, unique while the application is running
+    #[serde(rename = "Operation")]
+    pub operation_id: u64,
+    #[serde(rename = "Mod")]
+    pub mod_name: String,
+    #[serde(rename = "Phase")]
+    pub phase: ProgressPhase,
+    #[serde(rename = "Bytes Done")]
+    pub bytes_done: u64,
+    #[serde(rename = "Bytes Total")]
+    pub bytes_total: u64,
+    /// The average download speed of the current attempt, in bytes per second
+    #[serde(rename = "Speed")]
+    pub speed: f64,
+    /// The estimated number of seconds until the download finishes, if it can be estimated
+    #[serde(rename = "ETA")]
+    pub eta: Option<f64>,
+}
+
+/// Tracks the progress of an operation on a mod and reports it as it changes
+pub struct ProgressTracker {
+    progress: DownloadProgress,
+    started: Instant,
+    /// The number of bytes the current attempt started with, which do not count towards its speed
+    resumed_from: u64,
+    last_report: Option<Instant>,
+    report: Box<dyn Fn(&DownloadProgress) + Send>,
+}
+
+impl ProgressTracker {
+    /// Start tracking a new operation on a mod
+    /// # Arguments
+    /// * `mod_name` - The name of the mod the operation is on
+    /// * `report` - The function each report of the operation's progress is passed to
+    pub fn new<F: Fn(&DownloadProgress) + Send + 'static>(mod_name: &str, report: F) -> Self {
+        ProgressTracker {
+            progress: DownloadProgress {
+                operation_id: NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed),
+                mod_name: mod_name.to_string(),
+                phase: ProgressPhase::Queued,
+                bytes_done: 0,
+                bytes_total: 0,
+                speed: 0.0,
+                eta: None,
+            },
+            started: Instant::now(),
+            resumed_from: 0,
+            last_report: None,
+            report: Box::new(report),
+        }
+    }
+
+    /// Get the ID of the tracked operation
+    pub fn operation_id(&self) -> u64 {
+        self.progress.operation_id
+    }
+
+    /// Start a new attempt at downloading, which may pick up from an earlier attempt
+    /// # Arguments
+    /// * `bytes_done` - The number of bytes kept from an earlier attempt
+    /// * `bytes_total` - The size of the download
+    pub fn start_download(&mut self, bytes_done: u64, bytes_total: u64) {
+        self.started = Instant::now();
+        self.resumed_from = bytes_done;
+        self.progress.bytes_done = bytes_done;
+        self.progress.bytes_total = bytes_total;
+        self.progress.speed = 0.0;
+        self.progress.eta = None;
+        self.set_phase(ProgressPhase::Download);
+    }
+
+    /// Record that more of the download has been received
+    /// # Arguments
+    /// * `bytes` - The number of bytes received since the last call
+    pub fn advance(&mut self, bytes: u64) {
+        let progress = &mut self.progress;
+        progress.bytes_done = (progress.bytes_done + bytes).min(progress.bytes_total);
+        let elapsed = self.started.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            progress.speed = (progress.bytes_done - self.resumed_from) as f64 / elapsed;
+        }
+        if progress.speed > 0.0 {
+            progress.eta =
+                Some((progress.bytes_total - progress.bytes_done) as f64 / progress.speed);
+        }
+
+        let finished = progress.bytes_done == progress.bytes_total;
+        let due = self
+            .last_report
+            .map(|last| last.elapsed() >= REPORT_INTERVAL)
+            .unwrap_or(true);
+        if finished || due {
+            self.send();
+        }
+    }
+
+    /// Move the operation on to another phase and report it straight away
+    /// # Arguments
+    /// * `phase` - The phase the operation is now at
+    pub fn set_phase(&mut self, phase: ProgressPhase) {
+        self.progress.phase = phase;
+        if phase != ProgressPhase::Download {
+            self.progress.eta = None;
+        }
+        self.send();
+    }
+
+    /// Pass the current progress to the report function
+    fn send(&mut self) {
+        self.last_report = Some(Instant::now());
+        (self.report)(&self.progress);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::app::clock::now_millis;
+use crate::app::file_sets::matching_files;
+use crate::app::saves::is_save_file;
+use crate::error::Error;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The number of seconds in a day
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+/// How many save backups are kept
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SaveBackupLimits {
+    /// The most backups kept for each save slot, or 0 to keep any number
+    #[serde(rename = "Max Per Slot", default)]
+    pub max_per_slot: usize,
+    /// The most days a backup is kept for, or 0 to keep backups forever
+    #[serde(rename = "Max Age Days", default)]
+    pub max_age_days: u64,
+}
+
+impl Default for SaveBackupLimits {
+    fn default() -> Self {
+        SaveBackupLimits {
+            max_per_slot: 10,
+            max_age_days: 30,
+        }
+    }
+}
+
+/// A copy of the files of a save slot taken before they were overwritten
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SaveBackup {
+    #[serde(rename = "Slot")]
+    pub slot: u8,
+    /// The name of the backup's folder, unique within its slot
+    #[serde(rename = "ID")]
+    pub id: String,
+    /// The time the backup was taken, in milliseconds since the Unix epoch
+    #[serde(rename = "Created At")]
+    pub created_at: u64,
+    #[serde(rename = "Files")]
+    pub files: Vec<String>,
+}
+
+/// Whether a file belongs to a save slot, i.e. it is user{N}.dat or one of the game's backups of it
+/// # Arguments
+/// * `file_name` - The name of the file
+/// * `slot` - The number of the save slot
+fn is_slot_file(file_name: &str, slot: u8) -> bool {
+    is_save_file(file_name) && file_name.starts_with(format!("user{}.", slot).as_str())
+}
+
+/// Get the paths of a save slot's files in a folder
+/// # Arguments
+/// * `dir` - The folder to search
+/// * `slot` - The number of the save slot
+fn slot_files(dir: &Path, slot: u8) -> Vec<PathBuf> {
+    matching_files(dir, |name| is_slot_file(name, slot))
+}
+
+/// Get the folder the backups of a save slot are stored in
+/// # Arguments
+/// * `backups_dir` - The folder all save backups are stored in
+/// * `slot` - The number of the save slot
+fn slot_dir(backups_dir: &Path, slot: u8) -> PathBuf {
+    backups_dir.join(format!("Slot {}", slot))
+}
+
+/// Copy the current files of a save slot into a new backup, returning None if the slot is empty
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves from
+/// * `backups_dir` - The folder all save backups are stored in
+/// * `slot` - The number of the save slot
+pub fn backup_slot(
+    saves_dir: &Path,
+    backups_dir: &Path,
+    slot: u8,
+) -> Result<Option<SaveBackup>, Error> {
+    let files = slot_files(saves_dir, slot);
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let created_at = now_millis();
+    let mut id = created_at.to_string();
+    let mut suffix = 1;
+    while slot_dir(backups_dir, slot).join(id.as_str()).exists() {
+        suffix += 1;
+        id = format!("{}-{}", created_at, suffix);
+    }
+
+    let backup_dir = slot_dir(backups_dir, slot).join(id.as_str());
+    fs::create_dir_all(backup_dir.as_path())
+        .map_err(|e| Error::Io(format!("Failed to create {}: {}", backup_dir.display(), e)))?;
+    let mut file_names = vec![];
+    for file in files {
+        let file_name = file.file_name().unwrap().to_string_lossy().to_string();
+        if let Err(e) = fs::copy(file.as_path(), backup_dir.join(file_name.as_str())) {
+            let _ = fs::remove_dir_all(backup_dir.as_path());
+            return Err(Error::Io(format!(
+                "Failed to back up {}: {}",
+                file.display(),
+                e
+            )));
+        }
+        file_names.push(file_name);
+    }
+
+    info!("Backed up save slot {} as {}", slot, id);
+    Ok(Some(SaveBackup {
+        slot,
+        id,
+        created_at,
+        files: file_names,
+    }))
+}
+
+/// List the backups of one or all save slots, newest first
+/// # Arguments
+/// * `backups_dir` - The folder all save backups are stored in
+/// * `slot` - The number of the save slot, or None for every slot
+pub fn list_backups(backups_dir: &Path, slot: Option<u8>) -> Vec<SaveBackup> {
+    let slots = match slot {
+        Some(slot) => vec![slot],
+        None => (1..=4).collect(),
+    };
+
+    let mut backups = vec![];
+    for slot in slots {
+        let entries = match fs::read_dir(slot_dir(backups_dir, slot)) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let id = entry.file_name().to_string_lossy().to_string();
+            let created_at = match id.split('-').next().and_then(|time| time.parse().ok()) {
+                Some(created_at) if path.is_dir() => created_at,
+                _ => continue,
+            };
+            let files = slot_files(path.as_path(), slot)
+                .iter()
+                .filter_map(|file| file.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .collect();
+            backups.push(SaveBackup {
+                slot,
+                id,
+                created_at,
+                files,
+            });
+        }
+    }
+
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    backups
+}
+
+/// Replace the files of a save slot with those of one of its backups. The current files are
+/// backed up first so the restore itself can be undone.
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves from
+/// * `backups_dir` - The folder all save backups are stored in
+/// * `slot` - The number of the save slot
+/// * `id` - The ID of the backup to be restored
+pub fn restore_backup(
+    saves_dir: &Path,
+    backups_dir: &Path,
+    slot: u8,
+    id: &str,
+) -> Result<(), Error> {
+    // IDs are generated from timestamps, so anything else cannot name a backup
+    let backup_dir = slot_dir(backups_dir, slot).join(id);
+    let valid_id = !id.is_empty() && id.chars().all(|c| c.is_ascii_digit() || c == '-');
+    let backup_files = if valid_id {
+        slot_files(backup_dir.as_path(), slot)
+    } else {
+        vec![]
+    };
+    if backup_files.is_empty() {
+        return Err(Error::InvalidState(format!(
+            "Save slot {} has no backup {:?}.",
+            slot, id
+        )));
+    }
+
+    backup_slot(saves_dir, backups_dir, slot)?;
+    for file in slot_files(saves_dir, slot) {
+        fs::remove_file(file.as_path())
+            .map_err(|e| Error::Io(format!("Failed to remove {}: {}", file.display(), e)))?;
+    }
+    for file in backup_files {
+        let target = saves_dir.join(file.file_name().unwrap());
+        fs::copy(file.as_path(), target.as_path())
+            .map_err(|e| Error::Io(format!("Failed to restore {}: {}", target.display(), e)))?;
+    }
+
+    info!("Restored save slot {} from backup {}", slot, id);
+    Ok(())
+}
+
+/// Delete the backups that exceed the configured limits, returning the backups deleted
+/// # Arguments
+/// * `backups_dir` - The folder all save backups are stored in
+/// * `limits` - How many backups are kept
+pub fn prune_backups(backups_dir: &Path, limits: &SaveBackupLimits) -> Vec<SaveBackup> {
+    let oldest_kept = match limits.max_age_days {
+        0 => 0,
+        days => now_millis().saturating_sub(days * SECONDS_PER_DAY * 1000),
+    };
+
+    let mut pruned = vec![];
+    for slot in 1..=4 {
+        let backups = list_backups(backups_dir, Some(slot));
+        for (i, backup) in backups.into_iter().enumerate() {
+            let too_many = limits.max_per_slot != 0 && i >= limits.max_per_slot;
+            let too_old = backup.created_at < oldest_kept;
+            if !too_many && !too_old {
+                continue;
+            }
+
+            let backup_dir = slot_dir(backups_dir, slot).join(backup.id.as_str());
+            match fs::remove_dir_all(backup_dir.as_path()) {
+                Ok(_) => pruned.push(backup),
+                Err(e) => error!("Failed to delete {}: {}", backup_dir.display(), e),
+            }
+        }
+    }
+
+    if !pruned.is_empty() {
+        info!("Pruned {} save backups", pruned.len());
+    }
+    pruned
+}
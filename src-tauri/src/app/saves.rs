@@ -0,0 +1,175 @@
+use crate::app::file_sets::replace_files;
+use crate::error::Error;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Whether a file is one of the game's save files, e.g. user1.dat, or one of the backups the
+/// game keeps of them, e.g. user1.dat.bak or user1.dat.bak2
+/// # Arguments
+/// * `file_name` - The name of the file
+pub fn is_save_file(file_name: &str) -> bool {
+    let rest = match file_name
+        .strip_prefix("user")
+        .and_then(|name| name.find(".dat").map(|i| &name[i + ".dat".len()..]))
+    {
+        Some(rest) => rest,
+        None => return false,
+    };
+    rest.is_empty()
+        || rest
+            .strip_prefix(".bak")
+            .map(|number| number.chars().all(|c| c.is_ascii_digit()))
+            .unwrap_or(false)
+}
+
+/// How far a switch between sets of saves got
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum SaveSwitchPhase {
+    /// The saves in the saves folder are being copied into their set; they have not been touched
+    Stashing,
+    /// The saves in the saves folder are being replaced; their set holds a complete copy of them
+    Restoring,
+}
+
+/// A record of a switch between sets of saves that is in progress, written before the saves
+/// folder is changed so that an interrupted switch can be undone
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SaveSwitchJournal {
+    /// The set that the saves in the saves folder belonged to before the switch
+    #[serde(rename = "From")]
+    pub from: PathBuf,
+    /// The set being switched to
+    #[serde(rename = "To")]
+    pub to: PathBuf,
+    #[serde(rename = "Phase")]
+    pub phase: SaveSwitchPhase,
+}
+
+/// Read the journal of an interrupted switch, if there is one
+/// # Arguments
+/// * `journal_path` - The path to the journal
+pub fn read_journal(journal_path: &Path) -> Option<SaveSwitchJournal> {
+    let text = fs::read_to_string(journal_path).ok()?;
+    match serde_json::from_str(text.as_str()) {
+        Ok(journal) => Some(journal),
+        Err(e) => {
+            warn!("Failed to read save switch journal: {}", e);
+            None
+        }
+    }
+}
+
+/// Write the journal of a switch that is in progress
+/// # Arguments
+/// * `journal_path` - The path to the journal
+/// * `journal` - The state of the switch
+fn write_journal(journal_path: &Path, journal: &SaveSwitchJournal) -> Result<(), Error> {
+    let text = serde_json::to_string_pretty(journal)?;
+    fs::write(journal_path, text)
+        .map_err(|e| Error::Io(format!("Failed to write save switch journal: {}", e)))
+}
+
+/// Stash the saves currently in the saves folder into one set and restore another set in their
+/// place. The saves folder is only changed once the stashed set holds a complete copy of it.
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves from
+/// * `current_set` - The folder that the current saves belong to
+/// * `target_set` - The folder holding the saves to be restored
+/// * `journal_path` - The path to record the progress of the switch at
+pub fn switch_saves(
+    saves_dir: &Path,
+    current_set: &Path,
+    target_set: &Path,
+    journal_path: &Path,
+) -> Result<(), Error> {
+    if current_set == target_set {
+        return Ok(());
+    }
+    if journal_path.exists() {
+        return Err(Error::InvalidState(
+            "A previous switch of saves was interrupted and must be recovered first.".to_string(),
+        ));
+    }
+
+    let mut journal = SaveSwitchJournal {
+        from: current_set.to_path_buf(),
+        to: target_set.to_path_buf(),
+        phase: SaveSwitchPhase::Stashing,
+    };
+    write_journal(journal_path, &journal)?;
+    if let Err(e) = replace_files(saves_dir, current_set, is_save_file) {
+        // Nothing in the saves folder has been changed yet
+        let _ = fs::remove_file(journal_path);
+        return Err(e);
+    }
+
+    journal.phase = SaveSwitchPhase::Restoring;
+    write_journal(journal_path, &journal)?;
+    if let Err(e) = replace_files(target_set, saves_dir, is_save_file) {
+        // Put the stashed saves back, leaving the journal in place if even that fails
+        replace_files(current_set, saves_dir, is_save_file)
+            .map_err(|restore_error| restore_error.context(e.message()))?;
+        let _ = fs::remove_file(journal_path);
+        return Err(e);
+    }
+
+    fs::remove_file(journal_path)
+        .map_err(|e| Error::Io(format!("Failed to remove save switch journal: {}", e)))?;
+    info!(
+        "Switched saves from {} to {}",
+        current_set.display(),
+        target_set.display()
+    );
+    Ok(())
+}
+
+/// Undo a switch of saves that was interrupted, putting back the saves that were in the saves
+/// folder before it started. Returns the journal of the switch that was undone, if any.
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves from
+/// * `journal_path` - The path the progress of the switch was recorded at
+pub fn recover_saves(
+    saves_dir: &Path,
+    journal_path: &Path,
+) -> Result<Option<SaveSwitchJournal>, Error> {
+    if !journal_path.exists() {
+        return Ok(None);
+    }
+    let journal = read_journal(journal_path).ok_or_else(|| {
+        Error::Parse(
+            "The save switch journal is unreadable; the saves folder was left untouched."
+                .to_string(),
+        )
+    })?;
+
+    // While stashing, the saves folder is never changed, so there is nothing to put back
+    if journal.phase == SaveSwitchPhase::Restoring {
+        replace_files(journal.from.as_path(), saves_dir, is_save_file)?;
+    }
+
+    fs::remove_file(journal_path)
+        .map_err(|e| Error::Io(format!("Failed to remove save switch journal: {}", e)))?;
+    info!(
+        "Recovered saves of interrupted switch from {} to {}",
+        journal.from.display(),
+        journal.to.display()
+    );
+    Ok(Some(journal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_files_include_game_backups() {
+        assert!(is_save_file("user1.dat"));
+        assert!(is_save_file("user2.dat.bak"));
+        assert!(is_save_file("user3.dat.bak1"));
+        assert!(!is_save_file("user1.dat.tmp"));
+        assert!(!is_save_file("user1.dat.bak.tmp"));
+        assert!(!is_save_file("Benchwarp.GlobalSettings.json"));
+    }
+}
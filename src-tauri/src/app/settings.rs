@@ -1,11 +1,19 @@
 use crate::app::profile::Profile;
+use crate::app::save_backups::SaveBackupLimits;
+use crate::app::sources::{ModFeed, Sources};
 use crate::mod_links::local::LocalModLinks;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Settings {
+    /// The version of the Modding API that was last installed, or empty if it is not known
+    #[serde(rename = "Api Version", default)]
+    pub api_version: String,
     #[serde(rename = "Current Profile")]
     pub current_profile: String,
+    /// Feeds of mods that are merged with the official ModLinks, in order of precedence
+    #[serde(rename = "Custom Feeds", default)]
+    pub custom_feeds: Vec<ModFeed>,
     #[serde(rename = "Language")]
     pub language: String,
     #[serde(rename = "Mods Path")]
@@ -14,6 +22,10 @@ pub struct Settings {
     pub mod_links: LocalModLinks,
     #[serde(rename = "Profiles")]
     pub profiles: Vec<Profile>,
+    #[serde(rename = "Save Backups", default)]
+    pub save_backups: SaveBackupLimits,
+    #[serde(rename = "Sources", default)]
+    pub sources: Sources,
     #[serde(rename = "Theme")]
     pub theme: String,
     #[serde(rename = "Theme Path")]
@@ -23,13 +35,54 @@ pub struct Settings {
 impl Default for Settings {
     fn default() -> Self {
         Settings {
+            api_version: "".to_string(),
             current_profile: "".to_string(),
+            custom_feeds: vec![],
             mods_path: "".to_string(),
             language: "English".to_string(),
             mod_links: LocalModLinks::default(),
             profiles: vec![],
+            save_backups: SaveBackupLimits::default(),
+            sources: Sources::default(),
             theme: "Dark".to_string(),
             theme_path: "".to_string(),
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::sources::SourceUrls;
+
+    #[test]
+    fn partial_sources_keep_defaults() {
+        let json = r#"{
+            "Current Profile": "",
+            "Language": "English",
+            "Mods Path": "",
+            "Mod Links": { "Manifest": [] },
+            "Profiles": [],
+            "Sources": {
+                "Mod Links": { "Primary": "https://example.com/ModLinks.xml" },
+                "Api Links": { "Fallbacks": ["https://example.com/ApiLinks.xml"] }
+            },
+            "Theme": "Dark",
+            "Theme Path": ""
+        }"#;
+        let settings: Settings = serde_json::from_str(json).unwrap();
+        let defaults = Sources::default();
+
+        assert_eq!(
+            settings.sources.mod_links,
+            SourceUrls::new("https://example.com/ModLinks.xml")
+        );
+        assert_eq!(
+            settings.sources.api_links.urls(),
+            vec!["https://example.com/ApiLinks.xml"]
+        );
+        assert_eq!(settings.sources.api_linux, defaults.api_linux);
+        assert_eq!(settings.sources.api_mac, defaults.api_mac);
+        assert_eq!(settings.sources.api_windows, defaults.api_windows);
+    }
 }
\ No newline at end of file
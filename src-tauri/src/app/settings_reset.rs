@@ -0,0 +1,248 @@
+use crate::app::clock::now_millis;
+use crate::app::global_settings::GLOBAL_SETTINGS_SUFFIX;
+use crate::error::Error;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The file in each reset's backup folder that describes the reset
+const RESET_MANIFEST: &str = "Reset.json";
+
+/// A reset of a mod's settings, whose files were moved into a backup folder
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SettingsReset {
+    #[serde(rename = "Mod")]
+    pub mod_name: String,
+    /// The names of the settings files that were moved out of the saves folder
+    #[serde(rename = "Files")]
+    pub files: Vec<String>,
+    /// The time of the reset, in milliseconds since the Unix epoch
+    #[serde(rename = "Created At")]
+    pub created_at: u64,
+}
+
+/// Find the settings files in the saves folder that belong to a mod's DLLs
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves and settings from
+/// * `dll_names` - The names of the mod's DLLs, without their extension
+/// * `include_local_settings` - Whether to include the per-save local settings of the mod
+pub fn settings_files(
+    saves_dir: &Path,
+    dll_names: &[String],
+    include_local_settings: bool,
+) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(saves_dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let file_name = match path.file_name().and_then(|name| name.to_str()) {
+                Some(file_name) => file_name,
+                None => return false,
+            };
+            dll_names.iter().any(|dll_name| {
+                file_name == format!("{}{}", dll_name, GLOBAL_SETTINGS_SUFFIX)
+                    || (include_local_settings
+                        && file_name.starts_with(format!("{}.LocalSettings", dll_name).as_str()))
+            })
+        })
+        .collect()
+}
+
+/// Move a mod's settings files out of the saves folder into a new backup folder. A reset with
+/// no files is recorded as well, so that undoing it does not bring back an older reset.
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves and settings from
+/// * `backups_dir` - The folder all resets are backed up in
+/// * `mod_name` - The name of the mod whose settings are reset
+/// * `files` - The settings files to be moved
+pub fn reset_settings_files(
+    saves_dir: &Path,
+    backups_dir: &Path,
+    mod_name: &str,
+    files: &[PathBuf],
+) -> Result<SettingsReset, Error> {
+    let created_at = now_millis();
+    let mut reset = SettingsReset {
+        mod_name: mod_name.to_string(),
+        files: vec![],
+        created_at,
+    };
+
+    let mut id = created_at.to_string();
+    let mut suffix = 1;
+    while backups_dir.join(id.as_str()).exists() {
+        suffix += 1;
+        id = format!("{}-{}", created_at, suffix);
+    }
+    let backup_dir = backups_dir.join(id.as_str());
+    fs::create_dir_all(backup_dir.as_path())
+        .map_err(|e| Error::Io(format!("Failed to create {}: {}", backup_dir.display(), e)))?;
+
+    // Every file is copied before any is removed, so a failure leaves the settings untouched
+    for file in files {
+        let file_name = file.file_name().unwrap().to_string_lossy().to_string();
+        if let Err(e) = fs::copy(file, backup_dir.join(file_name.as_str())) {
+            let _ = fs::remove_dir_all(backup_dir.as_path());
+            return Err(Error::Io(format!(
+                "Failed to back up {}: {}",
+                file.display(),
+                e
+            )));
+        }
+        reset.files.push(file_name);
+    }
+    let manifest = serde_json::to_string_pretty(&reset)?;
+    if let Err(e) = fs::write(backup_dir.join(RESET_MANIFEST), manifest) {
+        let _ = fs::remove_dir_all(backup_dir.as_path());
+        return Err(Error::Io(format!(
+            "Failed to record reset of {}: {}",
+            mod_name, e
+        )));
+    }
+
+    for file in files {
+        if let Err(e) = fs::remove_file(file) {
+            warn!("Failed to remove {}: {}", file.display(), e);
+        }
+    }
+
+    info!(
+        "Reset settings of {} in {}: {:?}",
+        mod_name,
+        saves_dir.display(),
+        reset.files
+    );
+    Ok(reset)
+}
+
+/// Move the settings files of the most recent reset back into the saves folder, replacing any
+/// that were created since. Returns the reset that was undone, if there was one.
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves and settings from
+/// * `backups_dir` - The folder all resets are backed up in
+pub fn restore_last_reset(
+    saves_dir: &Path,
+    backups_dir: &Path,
+) -> Result<Option<SettingsReset>, Error> {
+    let entries = match fs::read_dir(backups_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(None),
+    };
+    // Backup folders are named after the time of the reset, with a suffix if several resets
+    // happened within the same millisecond
+    let last = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.to_string();
+            let (created_at, suffix): (u64, u32) = match id.split_once('-') {
+                Some((created_at, suffix)) => (created_at.parse().ok()?, suffix.parse().ok()?),
+                None => (id.parse().ok()?, 1),
+            };
+            Some(((created_at, suffix), entry.path()))
+        })
+        .filter(|(_, path)| path.join(RESET_MANIFEST).is_file())
+        .max_by_key(|(order, _)| *order);
+    let backup_dir = match last {
+        Some((_, backup_dir)) => backup_dir,
+        None => return Ok(None),
+    };
+
+    let manifest = fs::read_to_string(backup_dir.join(RESET_MANIFEST))
+        .map_err(|e| Error::Io(format!("Failed to read {}: {}", backup_dir.display(), e)))?;
+    let reset: SettingsReset = serde_json::from_str(manifest.as_str())
+        .map_err(|e| Error::Parse(format!("Failed to read {}: {}", backup_dir.display(), e)))?;
+    for file_name in &reset.files {
+        let source = backup_dir.join(file_name);
+        let target = saves_dir.join(file_name);
+        fs::copy(source.as_path(), target.as_path())
+            .map_err(|e| Error::Io(format!("Failed to restore {}: {}", target.display(), e)))?;
+    }
+
+    fs::remove_dir_all(backup_dir.as_path())
+        .map_err(|e| Error::Io(format!("Failed to remove {}: {}", backup_dir.display(), e)))?;
+    info!("Restored settings of {}: {:?}", reset.mod_name, reset.files);
+    Ok(Some(reset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create an empty folder for a test to work in
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("butterfly-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(dir.as_path());
+        fs::create_dir_all(dir.as_path()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn settings_files_match_dll_prefix_only() {
+        let saves_dir = test_dir("settings-prefix");
+        for file_name in [
+            "Map.GlobalSettings.json",
+            "BigMap.GlobalSettings.json",
+            "Map.LocalSettings.user1.json",
+            "BigMap.LocalSettings.user1.json",
+        ] {
+            fs::write(saves_dir.join(file_name), "{}").unwrap();
+        }
+
+        let mut files: Vec<String> =
+            settings_files(saves_dir.as_path(), &["Map".to_string()], true)
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec!["Map.GlobalSettings.json", "Map.LocalSettings.user1.json"]
+        );
+
+        fs::remove_dir_all(saves_dir.as_path()).unwrap();
+    }
+
+    #[test]
+    fn empty_reset_is_restored_instead_of_older_reset() {
+        let dir = test_dir("settings-empty");
+        let saves_dir = dir.join("Saves");
+        let backups_dir = dir.join("Backups");
+        fs::create_dir_all(saves_dir.as_path()).unwrap();
+        let settings_file = saves_dir.join("Map.GlobalSettings.json");
+        fs::write(settings_file.as_path(), "{}").unwrap();
+
+        reset_settings_files(
+            saves_dir.as_path(),
+            backups_dir.as_path(),
+            "Map",
+            std::slice::from_ref(&settings_file),
+        )
+        .unwrap();
+        let empty =
+            reset_settings_files(saves_dir.as_path(), backups_dir.as_path(), "Other", &[]).unwrap();
+        assert!(empty.files.is_empty());
+
+        let restored = restore_last_reset(saves_dir.as_path(), backups_dir.as_path()).unwrap();
+        assert_eq!(
+            restored.map(|reset| reset.mod_name),
+            Some("Other".to_string())
+        );
+        assert!(!settings_file.exists());
+
+        let restored = restore_last_reset(saves_dir.as_path(), backups_dir.as_path()).unwrap();
+        assert_eq!(
+            restored.map(|reset| reset.mod_name),
+            Some("Map".to_string())
+        );
+        assert!(settings_file.exists());
+
+        fs::remove_dir_all(dir.as_path()).unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+use crate::app::profile::{validate_profile, Profile};
+use crate::error::Error;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// The prefix every profile share code starts with
+const SHARE_CODE_PREFIX: &str = "BFP";
+
+/// The version of the format that share codes are generated in
+pub const SHARE_CODE_VERSION: u32 = 1;
+
+/// The largest decompressed profile accepted from a share code, in bytes
+const MAX_PROFILE_SIZE: u64 = 1024 * 1024;
+
+/// Encode a profile as a share code of the form BFP<version>.<base64url of the deflated JSON>
+/// # Arguments
+/// * `profile` - The profile to be shared
+pub fn encode_share_code(profile: &Profile) -> Result<String, Error> {
+    let json = serde_json::to_vec(profile).map_err(|e| {
+        Error::Parse(format!(
+            "Failed to serialize profile {:?}: {}",
+            profile.name, e
+        ))
+    })?;
+    let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+    let compressed = encoder
+        .write_all(json.as_slice())
+        .and_then(|_| encoder.finish())
+        .map_err(|e| {
+            Error::Io(format!(
+                "Failed to compress profile {:?}: {}",
+                profile.name, e
+            ))
+        })?;
+
+    Ok(format!(
+        "{}{}.{}",
+        SHARE_CODE_PREFIX,
+        SHARE_CODE_VERSION,
+        base64::encode_config(compressed, base64::URL_SAFE_NO_PAD)
+    ))
+}
+
+/// Decode and validate the profile in a share code
+/// # Arguments
+/// * `share_code` - The share code, which may contain whitespace from being pasted
+pub fn decode_share_code(share_code: &str) -> Result<Profile, Error> {
+    let share_code: String = share_code.chars().filter(|c| !c.is_whitespace()).collect();
+    let (header, payload) = share_code
+        .split_once('.')
+        .ok_or_else(|| Error::Parse("The share code is not in the expected format.".to_string()))?;
+    let version: u32 = header
+        .strip_prefix(SHARE_CODE_PREFIX)
+        .and_then(|version| version.parse().ok())
+        .ok_or_else(|| {
+            Error::Parse("The share code is not a Butterfly profile share code.".to_string())
+        })?;
+    if version > SHARE_CODE_VERSION {
+        return Err(Error::Parse(format!(
+            "The share code was generated in format version {}, but only versions up to {} are supported.",
+            version, SHARE_CODE_VERSION
+        )));
+    }
+
+    let compressed = base64::decode_config(payload, base64::URL_SAFE_NO_PAD)
+        .map_err(|e| Error::Parse(format!("The share code is corrupted: {}", e)))?;
+    let mut json = vec![];
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_PROFILE_SIZE + 1)
+        .read_to_end(&mut json)
+        .map_err(|e| Error::Parse(format!("The share code is corrupted: {}", e)))?;
+    if json.len() as u64 > MAX_PROFILE_SIZE {
+        return Err(Error::Parse(
+            "The profile in the share code is too large.".to_string(),
+        ));
+    }
+
+    let profile: Profile = serde_json::from_slice(json.as_slice()).map_err(|e| {
+        Error::Parse(format!(
+            "The share code does not contain a valid profile: {}",
+            e
+        ))
+    })?;
+    validate_profile(profile)
+}
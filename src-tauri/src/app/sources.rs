@@ -0,0 +1,133 @@
+use crate::error::Error;
+use log::warn;
+use reqwest::blocking::Client;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// The URLs a document can be fetched from; the primary URL is tried first,
+/// followed by each fallback in order
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct SourceUrls {
+    #[serde(rename = "Primary")]
+    pub primary: String,
+    #[serde(rename = "Fallbacks")]
+    pub fallbacks: Vec<String>,
+}
+
+impl SourceUrls {
+    /// Create a source with no fallbacks
+    /// # Arguments
+    /// * `primary` - The URL to fetch the document from
+    pub fn new(primary: &str) -> SourceUrls {
+        SourceUrls {
+            primary: primary.to_string(),
+            fallbacks: vec![],
+        }
+    }
+
+    /// All URLs of the source in the order they should be tried
+    pub fn urls(&self) -> Vec<&str> {
+        let mut urls = vec![self.primary.as_str()];
+        urls.extend(self.fallbacks.iter().map(|url| url.as_str()));
+        urls.retain(|url| !url.is_empty());
+        urls
+    }
+}
+
+/// Where Butterfly fetches ModLinks, ApiLinks and the Modding API from. Sources missing from
+/// the settings file, such as those added in later versions, keep their defaults.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Sources {
+    #[serde(rename = "Mod Links")]
+    pub mod_links: SourceUrls,
+    #[serde(rename = "Api Links")]
+    pub api_links: SourceUrls,
+    #[serde(rename = "Api Linux")]
+    pub api_linux: SourceUrls,
+    #[serde(rename = "Api Mac")]
+    pub api_mac: SourceUrls,
+    #[serde(rename = "Api Windows")]
+    pub api_windows: SourceUrls,
+}
+
+impl Default for Sources {
+    fn default() -> Self {
+        Sources {
+            mod_links: SourceUrls::new(
+                "https://raw.githubusercontent.com/hk-modding/modlinks/main/ModLinks.xml",
+            ),
+            api_links: SourceUrls::new(
+                "https://raw.githubusercontent.com/hk-modding/modlinks/main/ApiLinks.xml",
+            ),
+            api_linux: SourceUrls::new(
+                "https://github.com/hk-modding/api/releases/latest/download/ModdingApiLinux.zip",
+            ),
+            api_mac: SourceUrls::new(
+                "https://github.com/hk-modding/api/releases/latest/download/ModdingApiMac.zip",
+            ),
+            api_windows: SourceUrls::new(
+                "https://github.com/hk-modding/api/releases/latest/download/ModdingApiWin.zip",
+            ),
+        }
+    }
+}
+
+/// An additional feed of mods in the same format as ModLinks.xml
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ModFeed {
+    /// The name of the feed, shown as the source of each of its mods
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Source")]
+    pub source: SourceUrls,
+}
+
+/// Get the local path a file:// URL points to, or None if the URL is not a file URL
+/// # Arguments
+/// * `url` - The URL to convert
+pub fn local_path(url: &str) -> Option<PathBuf> {
+    match Url::parse(url) {
+        Ok(parsed) if parsed.scheme() == "file" => parsed.to_file_path().ok(),
+        _ => None,
+    }
+}
+
+/// Fetch the raw contents of a document, trying each of its URLs in order
+/// # Arguments
+/// * `client` - The client to send requests with
+/// * `source` - The URLs to fetch the document from
+pub fn fetch_bytes(client: &Client, source: &SourceUrls) -> Result<Vec<u8>, Error> {
+    let mut errors = vec![];
+    for url in source.urls() {
+        let result = match local_path(url) {
+            Some(path) => fs::read(path.as_path())
+                .map_err(|e| Error::Io(format!("Failed to read {}: {}", path.display(), e))),
+            None => client
+                .get(url)
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.bytes())
+                .map(|bytes| bytes.to_vec())
+                .map_err(|e| Error::Network(format!("Failed to fetch {}: {}", url, e))),
+        };
+        match result {
+            Ok(content) => return Ok(content),
+            Err(e) => {
+                warn!("{}", e);
+                errors.push(e.message().to_string());
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        return Err(Error::InvalidState(
+            "No URLs are configured for this source.".to_string(),
+        ));
+    }
+
+    Err(Error::Network(errors.join("; ")))
+}
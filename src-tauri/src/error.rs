@@ -0,0 +1,108 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use thiserror::Error as ThisError;
+
+/// An error that prevented an operation from completing, reported to the frontend with a code
+/// identifying its kind and a message describing it
+#[derive(Clone, Debug, PartialEq, ThisError)]
+pub enum Error {
+    /// A file or folder could not be read, written, moved or removed
+    #[error("{0}")]
+    Io(String),
+    /// A request to a remote source failed or returned an unsuccessful status
+    #[error("{0}")]
+    Network(String),
+    /// A document, save or share code is not in the expected format
+    #[error("{0}")]
+    Parse(String),
+    /// A download does not match the SHA256 hash it is listed with
+    #[error("{0}")]
+    HashMismatch(String),
+    /// The path to the game has not been found or selected
+    #[error("{0}")]
+    MissingGamePath(String),
+    /// The operation does not make sense given the current settings or files, e.g. a profile
+    /// that does not exist or a mod that is not installed
+    #[error("{0}")]
+    InvalidState(String),
+    /// The user backed out of the operation, e.g. by closing a file dialog
+    #[error("{0}")]
+    Cancelled(String),
+}
+
+impl Error {
+    /// Get the machine-readable code of the kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "Io",
+            Error::Network(_) => "Network",
+            Error::Parse(_) => "Parse",
+            Error::HashMismatch(_) => "HashMismatch",
+            Error::MissingGamePath(_) => "MissingGamePath",
+            Error::InvalidState(_) => "InvalidState",
+            Error::Cancelled(_) => "Cancelled",
+        }
+    }
+
+    /// Get the message describing the error
+    pub fn message(&self) -> &str {
+        match self {
+            Error::Io(message)
+            | Error::Network(message)
+            | Error::Parse(message)
+            | Error::HashMismatch(message)
+            | Error::MissingGamePath(message)
+            | Error::InvalidState(message)
+            | Error::Cancelled(message) => message.as_str(),
+        }
+    }
+
+    /// Prefix the message of the error with what was being done when it occurred, keeping its kind
+    /// # Arguments
+    /// * `context` - A description of what was being done, e.g. "Failed to switch saves"
+    pub fn context(self, context: &str) -> Error {
+        let message = format!("{}: {}", context, self.message());
+        match self {
+            Error::Io(_) => Error::Io(message),
+            Error::Network(_) => Error::Network(message),
+            Error::Parse(_) => Error::Parse(message),
+            Error::HashMismatch(_) => Error::HashMismatch(message),
+            Error::MissingGamePath(_) => Error::MissingGamePath(message),
+            Error::InvalidState(_) => Error::InvalidState(message),
+            Error::Cancelled(_) => Error::Cancelled(message),
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("Error", 2)?;
+        error.serialize_field("Code", self.code())?;
+        error.serialize_field("Message", self.message())?;
+        error.end()
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Network(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
+
+impl From<quick_xml::DeError> for Error {
+    fn from(error: quick_xml::DeError) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
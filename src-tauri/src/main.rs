@@ -4,42 +4,117 @@
 )]
 
 mod app;
+mod error;
 mod mod_links;
+mod save_data;
 
 use app::app::App;
-use app::profile::Profile;
+use app::downloads::{DownloadManager, DownloadQueue, DownloadTicket, QUEUE_EVENT};
+use app::file_sets::replace_files;
+use app::global_settings::{copy_global_settings, switch_global_settings};
+use app::profile::{
+    find_profile, parse_profiles, profile_folder_name, unique_profile_name, validate_profile_name,
+    ImportOutcome, ImportedProfile, ModPin, Profile, ProfileChanges, ProfileCollision, ProfileDiff,
+    ProfileLock, ProfileStep, ProfilesExport, EXPORT_FORMAT_VERSION,
+};
+use app::progress::{ProgressPhase, ProgressTracker, PROGRESS_EVENT};
+use app::save_backups::{
+    backup_slot, list_backups, prune_backups, restore_backup, SaveBackup, SaveBackupLimits,
+};
+use app::saves::{is_save_file, read_journal, recover_saves, switch_saves};
 use app::settings::Settings;
+use app::settings_reset::{reset_settings_files, restore_last_reset, settings_files};
+use app::share_code::{decode_share_code, encode_share_code};
+use app::sources::{fetch_bytes, ModFeed, SourceUrls, Sources};
 use directories::BaseDirs;
-use futures_util::StreamExt;
+use error::Error;
 use log::{error, info, warn, LevelFilter};
 use mod_links::api::*;
+use mod_links::cache::{feed_file_name, fetch_cached, CacheStatus};
+use mod_links::dependencies::resolve_dependencies;
 use mod_links::local::*;
 use mod_links::remote::*;
+use mod_links::staged_download::{discard_staged, download_staged};
+use mod_links::staged_install::{contains_dll, swap_into_place, validate_staged};
+use mod_links::version::{ModVersion, VersionChange};
 use native_dialog::{FileDialog, MessageDialog, MessageType};
-use open;
-use reqwest;
-use serde_json;
+use save_data::codec::{decode_save, encode_save};
+use save_data::slots::{list_slots, SaveSlot, SAVE_SLOTS};
+use serde::Serialize;
 use serde_json::{json, Value};
-use sha256::digest_file;
-use simple_logging;
+use sha256::{digest_bytes, digest_file};
 use std::cmp::min;
 use std::convert::Into;
 use std::env;
 use std::fs;
-use std::fs::{File, ReadDir};
-use std::io::{Cursor, Read, Write};
+use std::fs::File;
+use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::str::FromStr;
 use std::sync::{mpsc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
 use sysinfo::{ProcessExt, System, SystemExt};
-use tauri::{async_runtime, Manager, State};
+use tauri::{async_runtime, AppHandle, Manager, State};
 use unzip::Unzipper;
 
 struct AppState(Mutex<App>);
 
+/// The mod list JSON, the names of new, outdated and removed mods, and where the list came from
+type ModList = (String, Vec<String>, Vec<String>, Vec<String>, CacheStatus);
+
+/// Whether the Modding API is now enabled, and where ApiLinks came from if the API was downloaded
+type ApiToggle = (bool, Option<CacheStatus>);
+
 const SETTINGS_FOLDER: &str = "Butterfly";
 
+/// The name recorded as the source of mods listed on the official ModLinks
+const OFFICIAL_FEED: &str = "Official";
+
+/// The number of times a download is attempted before its hash mismatch is reported as an error
+const MAX_DOWNLOAD_ATTEMPTS: u8 = 3;
+
+/// The folder in settings that data owned by individual profiles is stored in
+const PROFILE_DATA_FOLDER: &str = "Profile Data";
+
+/// The folder in settings that data shared by profiles without their own is stored in
+const SHARED_DATA_FOLDER: &str = "Shared Data";
+
+/// The most pinned versions whose availability is checked at the same time during an import
+const MAX_CONCURRENT_PIN_CHECKS: usize = 4;
+
+/// How long a pinned version's download link has to answer before it counts as unavailable
+const PIN_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The folder in a profile's data that its mod global settings are stored in
+const GLOBAL_SETTINGS_FOLDER: &str = "GlobalSettings";
+
+/// The folder in a profile's data that its save files are stored in
+const SAVES_FOLDER: &str = "Saves";
+
+/// A folder in a profile's data that is never created, used as an empty set of files
+const EMPTY_FOLDER: &str = "Empty";
+
+/// The folder in settings that downloads are staged in until they have finished
+const DOWNLOADS_FOLDER: &str = "Downloads";
+
+/// The folder in the Managed folder that mods are extracted into before they replace what is
+/// in the Mods folder, so that it is on the same drive as the Mods folder
+const INSTALL_STAGING_FOLDER: &str = "Butterfly Staging";
+
+/// The folder in the install staging folder that mods replaced while applying a profile are
+/// kept in until the whole profile has been applied, so that they can be put back
+const REPLACED_MODS_FOLDER: &str = "Replaced";
+
+/// The folder in settings that backups of save files are stored in
+const SAVE_BACKUPS_FOLDER: &str = "Save Backups";
+
+/// The folder in settings that mod settings are moved to when they are reset
+const SETTINGS_BACKUPS_FOLDER: &str = "Settings Backups";
+
+/// The file in settings that records a switch of saves between profiles while it is in progress
+const SAVE_SWITCH_JOURNAL: &str = "Save Switch Journal.json";
+
 /// An array of possible paths to the folder containing the Hollow Knight executable
 static STATIC_PATHS: [&str; 6] = [
     "Program Files/Steam/steamapps/common/Hollow Knight",
@@ -63,20 +138,28 @@ static SUFFIXES: [&str; 3] = [
 fn setup_app() {
     exit_game();
     let app_state = AppState(Default::default());
-    check_settings(&app_state);
-    auto_detect(&app_state);
+    if let Err(e) = check_settings(&app_state) {
+        error!("Failed to load settings: {}", e);
+    }
+    if let Err(e) = auto_detect(&app_state) {
+        error!("Failed to detect the game path: {}", e);
+    }
     let app = tauri::Builder::default()
         .manage(app_state)
         .invoke_handler(tauri::generate_handler![
+            cancel_download,
             check_api_installed,
             create_profile,
             debug,
             delete_profile,
+            diff_profiles,
             disable_mod,
+            duplicate_profile,
             enable_mod,
             export_profiles,
-            fetch_current_download_progress,
+            export_save_json,
             fetch_current_profile,
+            fetch_download_queue,
             fetch_enabled_mods,
             fetch_installed_mods,
             fetch_language,
@@ -84,64 +167,51 @@ fn setup_app() {
             fetch_mod_list,
             fetch_profiles,
             fetch_theme_data,
+            generate_share_code,
             import_profiles,
             import_save,
+            import_save_json,
+            import_share_code,
             install_mod,
+            isolate_profile_saves,
+            list_save_backups,
+            list_save_slots,
             manually_install_mod,
+            merge_profiles,
             open_mods_folder,
             open_mod_read_me,
+            pause_downloads,
+            prune_save_backups,
+            recover_saves_switch,
+            rename_profile,
             reset_settings,
+            restore_save_backup,
+            restore_settings,
+            resume_downloads,
             set_language,
             set_profile,
             set_theme,
+            snapshot_global_settings,
+            snapshot_profile,
             toggle_api,
             uninstall_mod,
+            update_mods,
         ])
         .build(tauri::generate_context!())
         .expect("Failed to build tauri application.");
 
-    app.run(move |app_handle, event| match event {
-        tauri::RunEvent::ExitRequested { api, .. } => {
+    app.run(move |app_handle, event| {
+        if let tauri::RunEvent::ExitRequested { api, .. } = event {
             api.prevent_exit();
 
             let app_state = app_handle.state::<AppState>();
             let state = app_state.0.lock().unwrap();
-            let settings = state.settings.clone();
-            let base_dir = BaseDirs::new().unwrap();
-            let settings_dir: PathBuf = [base_dir.data_dir().to_str().unwrap(), SETTINGS_FOLDER]
-                .iter()
-                .collect();
-            if !settings_dir.exists() {
-                match fs::create_dir(settings_dir.as_path()) {
-                    Ok(_) => info!("Succesfully created settings folder."),
-                    Err(e) => error!("Failed to create settings folder: {}", e),
-                }
-            }
-            let settings_path: PathBuf = [settings_dir.to_str().unwrap(), "Settings.json"]
-                .iter()
-                .collect();
-            // Save or create a settings file
-            if settings_path.exists() {
-                let settings_file = File::options()
-                    .write(true)
-                    .open(settings_path.as_path())
-                    .unwrap();
-                match serde_json::to_writer_pretty(settings_file, &settings) {
-                    Ok(_) => info!("Successfully saved settings."),
-                    Err(e) => error!("Failed to save settings: {}", e),
-                }
-            } else {
-                let mut settings_file = File::create(settings_path.as_path()).unwrap();
-                let settings_string = serde_json::to_string(&state.settings).unwrap();
-                match settings_file.write_all(settings_string.as_bytes()) {
-                    Ok(_) => info!("Successfully created new settings file."),
-                    Err(e) => error!("Failed to create new settings file: {}", e),
-                }
+            if let Err(e) = save_settings(&state.settings) {
+                error!("Failed to save settings: {}", e);
             }
 
             app_handle.exit(0);
         }
-        _ => {}
     });
 }
 
@@ -149,23 +219,34 @@ fn main() {
     setup_app();
 }
 
+/// Cancel a queued or running download, removing anything it has written to the Mods folder
+/// # Arguments
+/// * `operation_id` - The ID of the operation the download belongs to
+/// * `app_handle` - The handle of the application, used to emit the download queue
+/// * `state` - The state of the application
+#[tauri::command]
+fn cancel_download(
+    operation_id: u64,
+    app_handle: AppHandle,
+    state: State<AppState>,
+) -> Result<(), Error> {
+    let downloads = state.0.lock().unwrap().downloads.clone();
+    downloads.cancel(operation_id)?;
+    info!("Cancelled download operation {}", operation_id);
+    emit_download_queue(&app_handle, &downloads);
+    Ok(())
+}
+
 /// Check and return whether the Modding API has been installed
 /// * `state` - The state of the application
 #[tauri::command]
-fn check_api_installed(state: State<AppState>) -> bool {
+fn check_api_installed(state: State<AppState>) -> Result<bool, Error> {
     let app_state = state.0.lock().unwrap();
-    let mods_path = &app_state.settings.mods_path;
+    let mods_path = game_mods_path(&app_state.settings)?;
     let managed_path: PathBuf = [mods_path.as_str(), ".."].iter().collect();
-    let vanilla_assembly: PathBuf = [
-        managed_path.to_str().unwrap(),
-        "Assembly-CSharp.dll.vanilla",
-    ]
-    .iter()
-    .collect();
-    let modded_assembly: PathBuf = [managed_path.to_str().unwrap(), "Assembly-CSharp.dll.modded"]
-        .iter()
-        .collect();
-    vanilla_assembly.exists() && !modded_assembly.exists()
+    let vanilla_assembly = managed_path.join("Assembly-CSharp.dll.vanilla");
+    let modded_assembly = managed_path.join("Assembly-CSharp.dll.modded");
+    Ok(vanilla_assembly.exists() && !modded_assembly.exists())
 }
 
 /// Create a new profile and save it to settings
@@ -174,80 +255,161 @@ fn check_api_installed(state: State<AppState>) -> bool {
 /// * `mod_names` - The name of the mods that will be included in the profile
 /// * `state` - The state of the application
 #[tauri::command]
-fn create_profile(profile_name: String, mod_names: Vec<String>, state: State<AppState>) {
+fn create_profile(
+    profile_name: String,
+    mod_names: Vec<String>,
+    state: State<AppState>,
+) -> Result<String, Error> {
     let mut app_state = state.0.lock().unwrap();
+    let profile_name = validate_profile_name(&app_state.settings.profiles, profile_name.as_str())?;
     app_state.settings.profiles.push(Profile {
-        name: profile_name,
+        name: profile_name.clone(),
         mods: mod_names,
+        pins: vec![],
+        global_settings: false,
+        saves: false,
     });
+
+    Ok(profile_name)
 }
 
 /// A tauri command that may be invoked from TypeScript for debugging purposes
 /// # Arguments
 /// * `msg` - The message to send from TypeScript
 #[tauri::command]
-fn debug(msg: String) {
+fn debug(msg: String) -> Result<(), Error> {
     info!("[DEBUG]\n\t\t{}", msg);
+    Ok(())
 }
 
-/// Delete a profile from settings
+/// Delete a profile from settings along with its data. A profile's own saves are backed up
+/// first so they are not lost. If it is the current profile, the shared saves and global
+/// settings are brought back in place of its own and no profile is current afterwards; if they
+/// cannot be brought back, nothing is deleted.
 /// # Arguments
 /// * `profile_name` - The name of the profile to be deleted
 /// * `state` - The state of the application
 #[tauri::command]
-fn delete_profile(profile_name: String, state: State<AppState>) {
+fn delete_profile(profile_name: String, state: State<AppState>) -> Result<(), Error> {
+    let saves_path = saves_dir()?;
+    let shared_dir = shared_data_dir()?;
+    let data_dir = profile_data_dir(profile_name.as_str())?;
     let mut app_state = state.0.lock().unwrap();
-    (*app_state)
+    let owned_global_settings = app_state
+        .settings
+        .profiles
+        .iter()
+        .any(|p| p.name == profile_name && p.global_settings);
+    let owned_saves = app_state
+        .settings
+        .profiles
+        .iter()
+        .any(|p| p.name == profile_name && p.saves);
+    let is_current = app_state.settings.current_profile == profile_name;
+
+    // The deleted profile's saves are in the saves folder if it is the current profile, and in
+    // its data otherwise, which is deleted along with it
+    if owned_saves {
+        let profile_saves = if is_current {
+            saves_path.clone()
+        } else {
+            data_dir.join(SAVES_FOLDER)
+        };
+        back_up_save_slots(
+            profile_saves.as_path(),
+            &SAVE_SLOTS,
+            &app_state.settings.save_backups,
+        )?;
+    }
+
+    // The deleted profile's global settings and saves are in use if it is the current profile,
+    // so bring back the shared ones in their place before anything is deleted
+    if owned_global_settings && is_current {
+        let shared_set = shared_dir.join(GLOBAL_SETTINGS_FOLDER);
+        copy_global_settings(shared_set.as_path(), saves_path.as_path())
+            .map_err(|e| e.context("Failed to restore shared global settings"))?;
+    }
+    if owned_saves && is_current {
+        let shared_saves = shared_dir.join(SAVES_FOLDER);
+        replace_files(shared_saves.as_path(), saves_path.as_path(), is_save_file)
+            .map_err(|e| e.context("Failed to restore shared saves"))?;
+    }
+
+    app_state
         .settings
         .profiles
         .retain(|p| p.name != profile_name);
+    if data_dir.exists() {
+        match fs::remove_dir_all(data_dir.as_path()) {
+            Ok(_) => info!("Deleted data of profile {:?}", profile_name),
+            Err(e) => error!("Failed to delete data of profile {:?}: {}", profile_name, e),
+        }
+    }
+    if is_current {
+        app_state.settings.current_profile = "".to_string();
+    }
+
+    Ok(())
+}
+
+/// Compare the mods of two profiles
+/// # Arguments
+/// * `first_profile` - The name of the first profile
+/// * `second_profile` - The name of the second profile
+/// * `state` - The state of the application
+#[tauri::command]
+fn diff_profiles(
+    first_profile: String,
+    second_profile: String,
+    state: State<AppState>,
+) -> Result<ProfileDiff, Error> {
+    let app_state = state.0.lock().unwrap();
+    let profiles = &app_state.settings.profiles;
+    let first = find_profile(profiles, first_profile.as_str())?;
+    let second = find_profile(profiles, second_profile.as_str())?;
+    Ok(first.diff(second))
 }
 
 /// Move a mod folder into the Disabled folder if it is located in the Mods folder
-/// # Argumentz`
+/// # Arguments
 /// *`mod_name` - The name of the mod folder to be moved into the Disabled folder
 /// * `state` - The state of the application
 #[tauri::command]
-fn disable_mod(mod_name: String, state: State<AppState>) {
+fn disable_mod(mod_name: String, state: State<AppState>) -> Result<(), Error> {
     info!("Disabling mod {:?}", mod_name);
+    set_mod_enabled(&mod_name, false, &state)?;
+    info!("Successfully moved mod {} to Disabled folder.", mod_name);
+    Ok(())
+}
+
+/// Save a copy of a profile under a new name
+/// # Arguments
+/// * `profile_name` - The name of the profile to be copied
+/// * `new_name` - The name of the copy, or None to derive one from the original name
+/// * `state` - The state of the application
+#[tauri::command]
+fn duplicate_profile(
+    profile_name: String,
+    new_name: Option<String>,
+    state: State<AppState>,
+) -> Result<String, Error> {
     let mut app_state = state.0.lock().unwrap();
-    let mods_path = &app_state.settings.mods_path;
-    let mod_path: PathBuf = [mods_path.clone(), mod_name.clone()].iter().collect();
-    let disabled_mods_path: PathBuf = [mods_path.to_string(), String::from("Disabled")]
-        .iter()
-        .collect();
-    let disabled_mod_path: PathBuf = [
-        mods_path.to_string(),
-        String::from("Disabled"),
-        mod_name.clone(),
-    ]
-    .iter()
-    .collect();
-    if !disabled_mods_path.exists() {
-        match fs::create_dir(disabled_mods_path.as_path()) {
-            Ok(_) => info!("Successfully created Disabled folder."),
-            Err(e) => error!("Failed to create Disabled folder: {}", e),
-        }
-    }
-    if mod_path.exists() {
-        match fs::rename(mod_path.as_path(), disabled_mod_path) {
-            Ok(_) => info!("Successfully moved mod {} to Disabled folder.", mod_name),
-            Err(e) => error!(
-                "Failed to move mod directory {:?} to Disabled: {}",
-                mod_path.to_str().unwrap(),
-                e
-            ),
-        }
-    } else {
-        warn!("Path {:?} does not exist.", mod_path.to_str().unwrap());
-    }
+    let profiles = &mut app_state.settings.profiles;
+    let profile = find_profile(profiles, profile_name.as_str())?.clone();
+    let new_name = match new_name {
+        Some(name) => validate_profile_name(profiles, name.as_str())?,
+        None => unique_profile_name(profiles, profile_name.as_str()),
+    };
 
-    let manifests = &app_state.settings.mod_links.manifests;
-    for i in 0..manifests.len() {
-        if app_state.settings.mod_links.manifests[i].name == mod_name {
-            app_state.settings.mod_links.manifests[i].enabled = false;
-        }
-    }
+    // The copy uses the shared global settings and saves rather than copying the original's
+    info!("Duplicating profile {:?} as {:?}", profile_name, new_name);
+    profiles.push(Profile {
+        name: new_name.clone(),
+        global_settings: false,
+        saves: false,
+        ..profile
+    });
+    Ok(new_name)
 }
 
 /// Move a mod folder out of the Disabled folder if it is there
@@ -255,228 +417,356 @@ fn disable_mod(mod_name: String, state: State<AppState>) {
 /// * `mod_name` - The name of the mod folder to move out of the Disabled folder
 /// * `state` - The state of the application
 #[tauri::command]
-fn enable_mod(mod_name: String, state: State<AppState>) {
+fn enable_mod(mod_name: String, state: State<AppState>) -> Result<(), Error> {
     info!("Enabling mod {:?}", mod_name);
+    set_mod_enabled(&mod_name, true, &state)?;
+    info!(
+        "Successfully moved mod {} out of Disabled folder.",
+        mod_name
+    );
+    Ok(())
+}
+
+/// Move a mod folder between the Mods folder and the Disabled folder and record
+/// whether it is enabled in settings
+/// # Arguments
+/// * `mod_name` - The name of the mod folder to be moved
+/// * `enabled` - Whether to move the mod into the Mods folder rather than the Disabled folder
+/// * `state` - The state of the application
+fn set_mod_enabled(mod_name: &str, enabled: bool, state: &State<AppState>) -> Result<(), Error> {
     let mut app_state = state.0.lock().unwrap();
-    let mods_path = &app_state.settings.mods_path;
-    let mod_path: PathBuf = [mods_path.to_string(), mod_name.clone()].iter().collect();
-    let disabled_mod_path: PathBuf = [
-        mods_path.to_string(),
-        String::from("Disabled"),
-        mod_name.clone(),
-    ]
-    .iter()
-    .collect();
-    if disabled_mod_path.exists() {
-        match fs::rename(disabled_mod_path.as_path(), mod_path.as_path()) {
-            Ok(_) => info!(
-                "Successfully moved mod {} out of Disabled folder.",
-                mod_name
-            ),
-            Err(e) => error!(
-                "Failed to move mod directory {:?} from Disabled: {}",
-                mod_path.to_str().unwrap(),
-                e
-            ),
-        }
+    let mods_path = game_mods_path(&app_state.settings)?;
+    let mod_path: PathBuf = [mods_path.as_str(), mod_name].iter().collect();
+    let disabled_mods_path: PathBuf = [mods_path.as_str(), "Disabled"].iter().collect();
+    let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", mod_name].iter().collect();
+    let (from, to) = if enabled {
+        (disabled_mod_path, mod_path)
     } else {
-        warn!("Path {:?} does not exist.", mod_path.to_str().unwrap());
+        (mod_path, disabled_mod_path)
+    };
+
+    if !disabled_mods_path.exists() {
+        match fs::create_dir(disabled_mods_path.as_path()) {
+            Ok(_) => info!("Successfully created Disabled folder."),
+            Err(e) => error!("Failed to create Disabled folder: {}", e),
+        }
+    }
+    if from.exists() {
+        fs::rename(from.as_path(), to.as_path()).map_err(|e| {
+            Error::Io(format!(
+                "Failed to move mod directory {:?} to {:?}: {}",
+                from, to, e
+            ))
+        })?;
+    } else if !to.exists() {
+        warn!("Path {:?} does not exist.", from);
     }
 
-    (*app_state)
+    app_state
         .settings
         .mod_links
         .manifests
         .iter_mut()
         .for_each(|m| {
             if m.name == mod_name {
-                m.enabled = true;
+                m.enabled = enabled;
             }
         });
+
+    Ok(())
 }
 
-/// Export a selected set of profiles to a JSON file
+/// Export a selected set of profiles to a JSON file, pinning every mod each profile needs
+/// to the version that is currently installed
 /// # Arguments
 /// * `profile_names` - The names of the profiles to be exported
 /// * `state` - The state of the application
 #[tauri::command]
-fn export_profiles(profile_names: Vec<String>, state: State<AppState>) -> bool {
+fn export_profiles(profile_names: Vec<String>, state: State<AppState>) -> Result<(), Error> {
     let app_state = state.0.lock().unwrap();
     let profiles = &app_state.settings.profiles;
     let mut export_array = vec![];
     for profile_name in profile_names {
         for profile in profiles {
             if profile.name == profile_name {
-                export_array.push(profile);
+                export_array.push(lock_profile(
+                    profile,
+                    &app_state.remote_mod_links.manifests,
+                    &app_state.settings.mod_links.manifests,
+                )?);
             }
         }
     }
 
-    let export_json = json!({ "Profiles": export_array });
-
-    let export_path = FileDialog::new()
-        .set_location("~")
-        .add_filter("JSON File", &["json"])
-        .show_save_single_file()
-        .unwrap();
-    let export_path = match export_path {
-        Some(path) => path,
-        None => {
-            error!("Path to export selected profiles to does not exist.");
-            return false;
-        }
+    let export_json = ProfilesExport {
+        format_version: EXPORT_FORMAT_VERSION,
+        api_version: app_state.settings.api_version.clone(),
+        profiles: export_array,
     };
 
-    let export_file = File::create(export_path.clone()).unwrap();
-    match serde_json::to_writer_pretty(export_file, &export_json) {
-        Ok(_) => info!(
-            "Successfully exported selected profiles to new file at {:?}",
-            export_path
-        ),
-        Err(e) => error!(
+    let export_path = selected_path(
+        FileDialog::new()
+            .set_location("~")
+            .add_filter("JSON File", &["json"])
+            .show_save_single_file(),
+        "export the selected profiles to",
+    )?;
+
+    let export_file = File::create(export_path.as_path())
+        .map_err(|e| Error::Io(format!("Failed to create {}: {}", export_path.display(), e)))?;
+    serde_json::to_writer_pretty(export_file, &export_json).map_err(|e| {
+        Error::Io(format!(
             "Failed to export selected profiles to new file at {:?}: {}",
             export_path, e
-        ),
+        ))
+    })?;
+    info!(
+        "Successfully exported selected profiles to new file at {:?}",
+        export_path
+    );
+    Ok(())
+}
+
+/// Pin every mod a profile needs, including dependencies, to an exact version. Existing pins
+/// are kept, installed mods are pinned to their installed version and any other mod is pinned
+/// to its version on ModLinks.
+/// # Arguments
+/// * `profile` - The profile to be locked
+/// * `remote_manifests` - The manifests fetched from ModLinks
+/// * `local_manifests` - The manifests saved in settings
+fn lock_profile(
+    profile: &Profile,
+    remote_manifests: &[RemoteModManifest],
+    local_manifests: &[LocalModManifest],
+) -> Result<ProfileLock, Error> {
+    let (listed, unlisted): (Vec<String>, Vec<String>) = profile
+        .mods
+        .iter()
+        .cloned()
+        .partition(|name| remote_manifests.iter().any(|m| &m.name == name));
+    let mut names = resolve_dependencies(&listed, remote_manifests)?;
+    names.extend(unlisted);
+    for pin in &profile.pins {
+        if !names.contains(&pin.name) {
+            names.push(pin.name.clone());
+        }
+    }
+
+    let mut mods = vec![];
+    for name in names {
+        let pin = match profile.pin(name.as_str()) {
+            Some(pin) => pin.clone(),
+            None => match local_manifests
+                .iter()
+                .find(|m| m.name == name && m.installed)
+            {
+                Some(manifest) => ModPin::from(manifest),
+                None => match remote_manifests.iter().find(|m| m.name == name) {
+                    Some(manifest) => ModPin::from(manifest),
+                    None => {
+                        warn!("Mod {:?} is neither installed nor on ModLinks.", name);
+                        continue;
+                    }
+                },
+            },
+        };
+        mods.push(pin);
+    }
+
+    Ok(ProfileLock {
+        name: profile.name.clone(),
+        mods,
+    })
+}
+
+/// Check whether the pinned versions of a profile's mods can still be installed, returning a
+/// warning for each pin that is neither the current version on ModLinks nor still downloadable.
+/// The download links of several pins are checked at once, each with a timeout.
+/// # Arguments
+/// * `profile` - The profile whose pins are to be checked
+/// * `remote_manifests` - The manifests fetched from ModLinks
+fn check_pins(profile: &Profile, remote_manifests: &[RemoteModManifest]) -> Vec<String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(PIN_CHECK_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    let current = |pin: &ModPin| remote_manifests.iter().find(|m| m.name == pin.name);
+    let unlisted: Vec<&ModPin> = profile
+        .pins
+        .iter()
+        .filter(|pin| match current(pin) {
+            Some(manifest) => {
+                manifest.version.change_to(&pin.version) != VersionChange::Identical
+                    || manifest.link.sha256 != pin.sha256
+            }
+            None => true,
+        })
+        .collect();
+
+    let mut warnings = vec![];
+    for pins in unlisted.chunks(MAX_CONCURRENT_PIN_CHECKS) {
+        let checks: Vec<_> = pins
+            .iter()
+            .map(|pin| {
+                let client = client.clone();
+                let link = pin.link.clone();
+                thread::spawn(move || {
+                    !link.is_empty()
+                        && client
+                            .head(link.as_str())
+                            .send()
+                            .map(|response| response.status().is_success())
+                            .unwrap_or(false)
+                })
+            })
+            .collect();
+
+        for (pin, check) in pins.iter().zip(checks) {
+            if check.join().unwrap_or(false) {
+                continue;
+            }
+            warnings.push(format!(
+                "Profile {:?} pins {} version {}, which is no longer available{}.",
+                profile.name,
+                pin.name,
+                pin.version,
+                match current(pin) {
+                    Some(manifest) => format!(" (ModLinks has version {})", manifest.version),
+                    None => " (the mod is not on ModLinks)".to_string(),
+                }
+            ));
+        }
     }
 
-    true
+    warnings
+}
+
+/// Decrypt a save slot and write its data to a JSON file
+/// # Arguments
+/// * `save_slot` - The number of the save slot to export
+#[tauri::command]
+fn export_save_json(save_slot: u8) -> Result<(), Error> {
+    let save_path = saves_dir()?.join(format!("user{}.dat", save_slot));
+    let bytes = fs::read(save_path.as_path())
+        .map_err(|e| Error::Io(format!("Failed to read save slot {}: {}", save_slot, e)))?;
+    let json = decode_save(bytes.as_slice())
+        .map_err(|e| e.context(format!("Failed to decode save slot {}", save_slot).as_str()))?;
+
+    let export_path = selected_path(
+        FileDialog::new()
+            .set_location("~")
+            .set_filename(format!("user{}.json", save_slot).as_str())
+            .add_filter("JSON File", &["json"])
+            .show_save_single_file(),
+        format!("export save slot {} to", save_slot).as_str(),
+    )?;
+
+    let text = serde_json::to_string_pretty(&json)?;
+    fs::write(export_path.as_path(), text)
+        .map_err(|e| Error::Io(format!("Failed to write {}: {}", export_path.display(), e)))?;
+    info!(
+        "Exported save slot {} as JSON to {:?}",
+        save_slot, export_path
+    );
+    Ok(())
 }
 
-/// Fetch the progress of the mod that is currently being downloaded.
+/// Fetch the active profile.
 /// * `state` - The state of the application
 #[tauri::command]
-fn fetch_current_download_progress(state: State<AppState>) -> u8 {
+fn fetch_current_profile(state: State<AppState>) -> Result<String, Error> {
     let app_state = state.0.lock().unwrap();
-    app_state.current_download_progress
+    Ok(app_state.settings.current_profile.clone())
 }
 
-/// Fetch the active profile.
+/// Fetch the downloads that are queued or running
+/// # Arguments
 /// * `state` - The state of the application
 #[tauri::command]
-fn fetch_current_profile(state: State<AppState>) -> String {
+fn fetch_download_queue(state: State<AppState>) -> Result<DownloadQueue, Error> {
     let app_state = state.0.lock().unwrap();
-    app_state.settings.current_profile.clone()
+    Ok(app_state.downloads.queue())
 }
 
 /// Fetch a list of enabled mods
 /// * `state` - The state of the application
 #[tauri::command]
-fn fetch_enabled_mods(state: State<AppState>) -> Vec<Value> {
+fn fetch_enabled_mods(state: State<AppState>) -> Result<Vec<Value>, Error> {
     let app_state = state.0.lock().unwrap();
     let manifests = &app_state.settings.mod_links.manifests;
-    let mod_count = manifests.len();
     let mut enabled_mods = vec![];
     let mods_path = &app_state.settings.mods_path;
     let disabled_path: PathBuf = [mods_path.as_str(), "Disabled"].iter().collect();
-    for i in 0..mod_count {
-        let mod_name = manifests[i].name.as_str();
-        let mod_version = manifests[i].name.as_str();
+    for manifest in manifests {
+        let mod_name = manifest.name.as_str();
+        let mod_version = &manifest.version;
         let mod_path: PathBuf = [mods_path.clone().as_str(), mod_name].iter().collect();
-        let disabled_mod_path: PathBuf = [
-            disabled_path.clone().into_os_string().to_str().unwrap(),
-            mod_name,
-        ]
-        .iter()
-        .collect();
+        let disabled_mod_path = disabled_path.join(mod_name);
         if mod_path.exists() && !disabled_mod_path.exists() {
             enabled_mods.push(json!({"Name": mod_name, "Version": mod_version}));
         }
     }
 
-    enabled_mods
+    Ok(enabled_mods)
 }
 
 /// Fetch a list of installed mods
 /// # Arguments
 /// * `state` - The state of the application
 #[tauri::command]
-fn fetch_installed_mods(state: State<AppState>) -> Vec<Value> {
+fn fetch_installed_mods(state: State<AppState>) -> Result<Vec<Value>, Error> {
     let app_state = state.0.lock().unwrap();
     let manifests = &app_state.settings.mod_links.manifests;
-    let mod_count = manifests.len();
 
     let mut installed_mods = vec![];
     let mods_path = &app_state.settings.mods_path;
     let disabled_path: PathBuf = [mods_path.as_str(), "Disabled"].iter().collect();
-    for i in 0..mod_count {
-        let mod_name = manifests[i].name.as_str();
-        let mod_version = manifests[i].name.as_str();
+    for manifest in manifests {
+        let mod_name = manifest.name.as_str();
+        let mod_version = &manifest.version;
         let mod_path: PathBuf = [mods_path.clone().as_str(), mod_name].iter().collect();
-        let disabled_mod_path: PathBuf = [
-            disabled_path.clone().into_os_string().to_str().unwrap(),
-            mod_name,
-        ]
-        .iter()
-        .collect();
+        let disabled_mod_path = disabled_path.join(mod_name);
         if mod_path.exists() || disabled_mod_path.exists() {
             installed_mods.push(json!({"Name": mod_name, "Version": mod_version}));
         }
     }
 
-    installed_mods
+    Ok(installed_mods)
 }
 
 /// Fetch the application's current language
 /// # Arguments
 /// * `state` - The state of the application
 #[tauri::command]
-fn fetch_language(state: State<AppState>) -> String {
+fn fetch_language(state: State<AppState>) -> Result<String, Error> {
     let app_state = state.0.lock().unwrap();
-    app_state.settings.language.clone()
+    Ok(app_state.settings.language.clone())
 }
 
 /// Fetch a stringified JSON containing data on mods installed that are not on ModLinks.xml
 /// # Arguments
 /// * `state` - The state of the application
 #[tauri::command]
-fn fetch_manually_installed_mods(state: State<AppState>) -> String {
+fn fetch_manually_installed_mods(state: State<AppState>) -> Result<String, Error> {
     let app_state = state.0.lock().unwrap();
     let mut manually_installed_mods = vec![];
-    let mods_path = &app_state.settings.mods_path;
+    let mods_path = game_mods_path(&app_state.settings)?;
     let manifests = &app_state.settings.mod_links.manifests;
-    let mut path_bufs = vec![];
-    let path_buf = PathBuf::from_str(mods_path.as_str()).unwrap();
-    path_bufs.push(path_buf);
-    let disabled_path_buf: PathBuf = [mods_path.as_str(), "Disabled"].iter().collect();
-    if disabled_path_buf.exists() {
-        path_bufs.push(disabled_path_buf);
-    }
-
-    for path in path_bufs {
-        'folder_loop: for mod_folder in fs::read_dir(path).unwrap() {
-            match mod_folder.as_ref().unwrap().path().extension() {
-                Some(_) => continue,
-                None => (),
-            }
-
-            for i in 0..manifests.len() {
-                let mod_path = mod_folder.as_ref().unwrap().path();
-                let mod_name = mod_path.file_name().unwrap().to_str().unwrap();
-                let manifest_name = manifests[i].name.as_str();
-                if mod_name == manifest_name {
-                    continue 'folder_loop;
-                }
+    let disabled_path: PathBuf = [mods_path.as_str(), "Disabled"].iter().collect();
+    let folders = [
+        (PathBuf::from(mods_path.as_str()), true),
+        (disabled_path, false),
+    ];
+
+    for (path, enabled) in folders {
+        for mod_name in mod_folders(path.as_path()) {
+            if manifests.iter().any(|m| m.name == mod_name) {
+                continue;
             }
 
-            for mod_file in fs::read_dir(mod_folder.as_ref().unwrap().path()).unwrap() {
-                let file_path = mod_file.unwrap().path();
-                match file_path.extension() {
-                    Some(ext) => {
-                        if ext.to_str().unwrap() == "dll" {
-                            let mod_path = mod_folder.as_ref().unwrap().path();
-                            let mod_name = mod_path.file_name().unwrap().to_str().unwrap();
-                            let enabled =
-                                !String::from(mod_path.to_str().unwrap()).contains("Disabled");
-                            let mod_json = json!({"name": mod_name, "enabled": enabled});
-                            manually_installed_mods.push(mod_json);
-                            break;
-                        }
-                    }
-                    None => warn!(
-                        "File {:?} has no extension, may be a directory.",
-                        file_path.to_str().unwrap()
-                    ),
-                }
+            if contains_dll(path.join(mod_name.as_str()).as_path()) {
+                let mod_json = json!({"name": mod_name, "enabled": enabled});
+                manually_installed_mods.push(mod_json);
             }
         }
     }
@@ -484,614 +774,2250 @@ fn fetch_manually_installed_mods(state: State<AppState>) -> String {
     let manually_installed_json = json!(manually_installed_mods);
     let manual_json = manually_installed_json.to_string();
     info!("Manual JSON: {}", manual_json);
-    manual_json
+    Ok(manual_json)
 }
 
-/// Load and return the list of mods from https://raw.githubusercontent.com/hk-modding/modlinks/main/ModLinks.xml
+/// Load and return the list of mods from the configured ModLinks sources along with the names of new, outdated and removed mods compared to the previously saved list,
+/// falling back to the last successfully fetched list when offline
 /// # Arguments
-/// * `state` - The state of the application
+/// * `app_handle` - The handle of the application
 #[tauri::command]
-fn fetch_mod_list(state: State<AppState>) -> (String, Vec<String>, Vec<String>) {
-    let mut app_state = state.0.lock().unwrap();
-    let client = reqwest::blocking::Client::new();
-    let mut new_mods = vec![];
-    let mut mods_json = "".to_string();
-    let mut outdated_mods = vec![];
-    match client
-        .get("https://raw.githubusercontent.com/hk-modding/modlinks/main/ModLinks.xml")
-        .send()
-    {
-        Ok(response) => {
-            let content = response.text().expect("Failed to get content of mod list.");
-            let mut remote_mod_links = RemoteModLinks::new();
-            match quick_xml::de::from_str(content.as_str()) {
-                Ok(value) => {
-                    info!("Successfully parsed ModLinks XML");
-                    remote_mod_links = value;
-                }
-                Err(e) => error!("Failed to parse ModLinks XML: {}", e),
-            }
+async fn fetch_mod_list(app_handle: AppHandle) -> Result<ModList, Error> {
+    run_blocking(app_handle, |_, state| fetch_remote_mod_list(state)).await
+}
 
-            let saved_manifests: Vec<LocalModManifest> = vec![];
-
-            // If save mod links are empty, then this is a first run of the app.
-            if saved_manifests.len() > 0 {
-                for manifest in remote_mod_links.clone().manifests {
-                    if !saved_manifests
-                        .clone()
-                        .into_iter()
-                        .map(|m| serde_json::to_string(&m.name).unwrap())
-                        .collect::<Vec<String>>()
-                        .contains(&manifest.name)
-                    {
-                        new_mods.push(manifest.name.clone());
-                    }
+/// Fetch the list of mods from the configured sources, blocking until every source has
+/// responded. The state is only locked before and after the sources are fetched.
+/// # Arguments
+/// * `state` - The state of the application
+fn fetch_remote_mod_list(state: &State<AppState>) -> Result<ModList, Error> {
+    let source: SourceUrls;
+    let custom_feeds: Vec<ModFeed>;
+    let mods_path: String;
+    {
+        let app_state = state.0.lock().unwrap();
+        source = app_state.settings.sources.mod_links.clone();
+        custom_feeds = app_state.settings.custom_feeds.clone();
+        mods_path = app_state.settings.mods_path.clone();
+    }
 
-                    if saved_manifests
-                        .clone()
-                        .into_iter()
-                        .map(|m| serde_json::to_string(&m.name).unwrap())
-                        .collect::<Vec<String>>()
-                        .contains(&manifest.name)
-                        && !saved_manifests
-                            .clone()
-                            .into_iter()
-                            .map(|m| serde_json::to_string(&m.version).unwrap())
-                            .collect::<Vec<String>>()
-                            .contains(&manifest.version)
-                    {
-                        outdated_mods.push(manifest.name);
-                    }
-                }
-            }
+    let client = reqwest::blocking::Client::new();
+    let (official_mod_links, mut cache_status) = fetch_cached(
+        &client,
+        &source,
+        cache_dir()?.as_path(),
+        "ModLinks.xml",
+        |content| {
+            quick_xml::de::from_str::<RemoteModLinks>(content)
+                .map_err(|e| Error::Parse(format!("Failed to parse ModLinks XML: {}", e)))
+        },
+    )
+    .map_err(|e| {
+        error!("Failed to fetch mod links: {}", e);
+        e
+    })?;
+    info!("Successfully parsed ModLinks XML");
+    if cache_status.stale {
+        warn!("Using cached ModLinks XML from {}", cache_status.fetched_at);
+    }
 
-            let mod_count = remote_mod_links.manifests.len();
-
-            let mods_path = &app_state.settings.mods_path;
-            let disabled_path: PathBuf = [mods_path.as_str(), "Disabled"].iter().collect();
-            for i in 0..mod_count {
-                let mod_name = &remote_mod_links.manifests[i].name;
-                let mod_path: PathBuf = [mods_path.clone(), mod_name.clone()].iter().collect();
-                let disabled_mod_path: PathBuf = [
-                    disabled_path.clone().into_os_string().to_str().unwrap(),
-                    mod_name.as_str(),
-                ]
-                .iter()
-                .collect();
-                if mod_path.exists() || disabled_mod_path.exists() {
-                    remote_mod_links.manifests[i].installed = true;
+    // The official feed takes precedence over custom feeds, which take precedence
+    // over each other in the order they are listed in settings
+    let mut remote_mod_links = RemoteModLinks::new();
+    remote_mod_links.merge(official_mod_links, OFFICIAL_FEED);
+    for feed in custom_feeds {
+        let file_name = feed_file_name(&feed.source);
+        match fetch_cached(
+            &client,
+            &feed.source,
+            cache_dir()?.as_path(),
+            file_name.as_str(),
+            |content| {
+                quick_xml::de::from_str::<RemoteModLinks>(content).map_err(|e| {
+                    Error::Parse(format!("Failed to parse feed {:?}: {}", feed.name, e))
+                })
+            },
+        ) {
+            Ok((feed_mod_links, status)) => {
+                if status.stale {
+                    warn!(
+                        "Using cached feed {:?} from {}",
+                        feed.name, status.fetched_at
+                    );
+                    cache_status.stale = true;
+                    cache_status.fetched_at = min(cache_status.fetched_at, status.fetched_at);
                 }
-                if mod_path.exists() && !disabled_mod_path.exists() {
-                    remote_mod_links.manifests[i].enabled = true;
+                let conflicts = remote_mod_links.merge(feed_mod_links, feed.name.as_str());
+                if !conflicts.is_empty() {
+                    warn!(
+                        "Ignoring mods from feed {:?} that are already listed by another feed: {:?}",
+                        feed.name, conflicts
+                    );
                 }
             }
+            Err(e) => error!("Failed to fetch feed {:?}: {}", feed.name, e),
+        }
+    }
+
+    let mod_count = remote_mod_links.manifests.len();
 
-            mods_json = serde_json::to_string_pretty(&remote_mod_links).unwrap();
-            app_state.settings.mod_links = serde_json::from_str(mods_json.as_str()).unwrap();
+    let disabled_path: PathBuf = [mods_path.as_str(), "Disabled"].iter().collect();
+    for i in 0..mod_count {
+        let mod_name = &remote_mod_links.manifests[i].name;
+        let mod_path: PathBuf = [mods_path.clone(), mod_name.clone()].iter().collect();
+        let disabled_mod_path = disabled_path.join(mod_name.as_str());
+        if mod_path.exists() || disabled_mod_path.exists() {
+            remote_mod_links.manifests[i].installed = true;
+        }
+        if mod_path.exists() && !disabled_mod_path.exists() {
+            remote_mod_links.manifests[i].enabled = true;
         }
-        Err(e) => error!("Failed to fetch mod links: {}", e),
     }
 
-    (mods_json.to_string(), new_mods, outdated_mods)
+    let mut app_state = state.0.lock().unwrap();
+    let saved_mod_links = app_state.settings.mod_links.clone();
+    let changes = remote_mod_links.compare(&saved_mod_links);
+    info!(
+        "New mods: {:?}, outdated mods: {:?}, removed mods: {:?}",
+        changes.new_mods, changes.outdated_mods, changes.removed_mods
+    );
+
+    app_state.remote_mod_links = remote_mod_links.clone();
+    let mods_json = serde_json::to_string_pretty(&remote_mod_links)?;
+    let mut mod_links: LocalModLinks = serde_json::from_str(mods_json.as_str())?;
+    // Saved versions and links of installed mods describe what is on disk, so keep
+    // them until the mod is actually updated
+    for manifest in mod_links.manifests.iter_mut() {
+        if !manifest.installed {
+            continue;
+        }
+        if let Some(saved_manifest) = saved_mod_links
+            .manifests
+            .iter()
+            .find(|m| m.name == manifest.name)
+        {
+            manifest.version = saved_manifest.version.clone();
+            manifest.link = saved_manifest.link.clone();
+        }
+    }
+    // Manually installed mods are not on ModLinks, so carry them over as well
+    for saved_manifest in saved_mod_links.manifests {
+        if saved_manifest.link.link.is_empty()
+            && !mod_links
+                .manifests
+                .iter()
+                .any(|m| m.name == saved_manifest.name)
+        {
+            mod_links.manifests.push(saved_manifest);
+        }
+    }
+    app_state.settings.mod_links = mod_links;
+
+    Ok((
+        mods_json,
+        changes.new_mods,
+        changes.outdated_mods,
+        changes.removed_mods,
+        cache_status,
+    ))
 }
 
 /// Fetch all mod profiles
 /// # Arguments
 /// * `state` - The state of the application
 #[tauri::command]
-fn fetch_profiles(state: State<AppState>) -> (String, String) {
+fn fetch_profiles(state: State<AppState>) -> Result<(String, String), Error> {
     let app_state = state.0.lock().unwrap();
-    let profiles = serde_json::to_string_pretty(&app_state.settings.profiles).unwrap();
+    let profiles = serde_json::to_string_pretty(&app_state.settings.profiles)?;
     let current_profile = &app_state.settings.current_profile;
-    (profiles, current_profile.to_string())
+    Ok((profiles, current_profile.to_string()))
 }
 
 /// Fetch theme data
 /// # Arguments
 /// * `state` - The state of the application
 #[tauri::command]
-fn fetch_theme_data(state: State<AppState>) -> (String, String, String) {
+fn fetch_theme_data(state: State<AppState>) -> Result<(String, String, String), Error> {
     let app_state = state.0.lock().unwrap();
     let theme_path = &app_state.settings.theme_path;
     let theme = &app_state.settings.theme;
     let mut css = "".to_string();
     if theme_path.as_str() != "" {
-        let mut css_file = File::options().read(true).open(theme_path.clone()).unwrap();
-        match css_file.read_to_string(&mut css) {
-            Ok(css) => info!("Successfully read in from CSS file: {}", css),
-            Err(e) => error!("Failed to read in from CSS File: {}", e),
-        }
+        css = fs::read_to_string(theme_path.as_str())
+            .map_err(|e| Error::Io(format!("Failed to read in from CSS File: {}", e)))?;
+        info!("Successfully read in from CSS file: {}", theme_path);
     }
-    (theme.to_string(), theme_path.to_string(), css.to_string())
+    Ok((theme.to_string(), theme_path.to_string(), css))
 }
 
-/// Import a set of profiles from a JSON file
+/// Import a set of profiles from a JSON file, reporting what happened to each profile
 /// # Arguments
-/// * `state` - The state of the application
+/// * `on_collision` - What to do with profiles whose name is already taken
+/// * `app_handle` - The handle of the application
 #[tauri::command]
-fn import_profiles(state: State<AppState>) {
-    let import_path = FileDialog::new()
-        .set_location("~")
-        .add_filter("JSON File", &["json"])
-        .show_open_single_file()
-        .unwrap();
-    let import_path = match import_path {
-        Some(path) => path,
-        None => {
-            error!("Path to imported profiles JSON does not exist.");
-            return;
-        }
-    };
+async fn import_profiles(
+    on_collision: ProfileCollision,
+    app_handle: AppHandle,
+) -> Result<Vec<ImportedProfile>, Error> {
+    run_blocking(app_handle, move |app_handle, state| {
+        let import_path = on_main_thread(app_handle, || {
+            selected_path(
+                FileDialog::new()
+                    .set_location("~")
+                    .add_filter("JSON File", &["json"])
+                    .show_open_single_file(),
+                "import profiles from",
+            )
+        })??;
 
-    let imported_json_string = fs::read_to_string(import_path).unwrap();
-    let mut imported_json: Value = serde_json::from_str(imported_json_string.as_str()).unwrap();
-    let imported_profiles = imported_json["Profiles"].as_array_mut().unwrap();
+        let imported_json_string = fs::read_to_string(import_path.as_path()).map_err(|e| {
+            Error::Io(format!("Failed to read {}: {}", import_path.display(), e))
+        })?;
+        let (profiles, api_version) = parse_profiles(imported_json_string.as_str())?;
+        let mut results = add_imported_profiles(profiles, on_collision, state);
 
-    let mut app_state = state.0.lock().unwrap();
-    for profile in imported_profiles {
-        app_state.settings.profiles.push(Profile {
-            name: profile["Name"].to_string(),
-            mods: profile["Mods"]
-                .as_array()
-                .unwrap()
-                .to_vec()
-                .iter()
-                .map(|value| value.to_string())
-                .collect(),
-        });
-    }
+        let installed_api_version: String;
+        {
+            let app_state = state.0.lock().unwrap();
+            installed_api_version = app_state.settings.api_version.clone();
+        }
+        // Versions are only compared when both are known, since older exports and installs
+        // did not record them
+        if !api_version.is_empty()
+            && !installed_api_version.is_empty()
+            && api_version != installed_api_version
+        {
+            let warning = format!(
+                "The profiles were exported with version {} of the Modding API, but version {} is installed.",
+                api_version, installed_api_version
+            );
+            warn!("{}", warning);
+            for result in results.iter_mut() {
+                result.warnings.push(warning.clone());
+            }
+        }
+
+        Ok(results)
+    })
+    .await
 }
 
-/// Import a save into the game's saves folder.
+/// Generate a share code for a profile
 /// # Arguments
-/// * `save_slot` - The number of the save slot to replace
+/// * `profile_name` - The name of the profile to be shared
+/// * `include_pins` - Whether to pin every mod the profile needs to its installed version
+/// * `state` - The state of the application
 #[tauri::command]
-fn import_save(save_slot: i32) {
-    let import_path = FileDialog::new()
-        .set_location("~")
-        .add_filter("Save file", &["dat"])
-        .show_open_single_file()
-        .unwrap();
-    let import_path = match import_path {
-        Some(path) => path,
-        None => {
-            error!("Import path is not valid.");
-            return;
+fn generate_share_code(
+    profile_name: String,
+    include_pins: bool,
+    state: State<AppState>,
+) -> Result<String, Error> {
+    let app_state = state.0.lock().unwrap();
+    let profile = find_profile(&app_state.settings.profiles, profile_name.as_str())?;
+    let shared = if include_pins {
+        let lock = lock_profile(
+            profile,
+            &app_state.remote_mod_links.manifests,
+            &app_state.settings.mod_links.manifests,
+        )?;
+        Profile {
+            pins: lock.mods,
+            global_settings: false,
+            saves: false,
+            ..profile.clone()
+        }
+    } else {
+        Profile {
+            pins: vec![],
+            global_settings: false,
+            saves: false,
+            ..profile.clone()
         }
     };
 
-    let base_dir = BaseDirs::new().unwrap();
-    let save_path: String;
-    match env::consts::OS {
-        "linux" => {
-            save_path = format!(
-                "{}/unity3d/Team Cherry/Hollow Knight/user{}.dat",
-                base_dir.config_dir().display(),
-                save_slot
-            );
-        }
-        "mac" => {
-            save_path = format!(
-                "{}/unity.Team Cherry.Hollow Knight/user{}.dat",
-                base_dir.data_dir().display(),
-                save_slot
-            );
-        }
-        "windows" => {
-            save_path = format!(
-                "{}/../LocalLow/Team Cherry/Hollow Knight/user{}.dat",
-                base_dir.data_dir().display(),
-                save_slot
+    let share_code = encode_share_code(&shared)?;
+    info!(
+        "Generated share code for profile {:?} ({} characters).",
+        profile_name,
+        share_code.len()
+    );
+    Ok(share_code)
+}
+
+/// Import a profile from a share code
+/// # Arguments
+/// * `share_code` - The share code of the profile
+/// * `on_collision` - What to do with the profile if its name is already taken
+/// * `app_handle` - The handle of the application
+#[tauri::command]
+async fn import_share_code(
+    share_code: String,
+    on_collision: ProfileCollision,
+    app_handle: AppHandle,
+) -> Result<ImportedProfile, Error> {
+    run_blocking(app_handle, move |_, state| {
+        let profile = decode_share_code(share_code.as_str())?;
+        let mut results = add_imported_profiles(vec![profile], on_collision, state);
+        Ok(results.remove(0))
+    })
+    .await
+}
+
+/// Add imported profiles to settings, flagging mods that are not on ModLinks and pinned
+/// versions that are no longer available
+/// # Arguments
+/// * `profiles` - The validated imported profiles
+/// * `on_collision` - What to do with profiles whose name is already taken
+/// * `state` - The state of the application
+fn add_imported_profiles(
+    profiles: Vec<Profile>,
+    on_collision: ProfileCollision,
+    state: &State<AppState>,
+) -> Vec<ImportedProfile> {
+    let remote_manifests: Vec<RemoteModManifest>;
+    {
+        let app_state = state.0.lock().unwrap();
+        remote_manifests = app_state.remote_mod_links.manifests.clone();
+    }
+
+    let mut results = vec![];
+    for profile in profiles {
+        let mut unknown_mods: Vec<String> = vec![];
+        let names = profile
+            .mods
+            .iter()
+            .chain(profile.pins.iter().map(|pin| &pin.name));
+        for name in names {
+            if !remote_manifests.iter().any(|m| &m.name == name) && !unknown_mods.contains(name) {
+                unknown_mods.push(name.clone());
+            }
+        }
+        let warnings = check_pins(&profile, &remote_manifests);
+
+        let mut app_state = state.0.lock().unwrap();
+        let profiles = &mut app_state.settings.profiles;
+        let name = profile.name.clone();
+        let existing = profiles.iter().position(|p| p.name == name);
+        let (outcome, saved_as) = match (existing, on_collision) {
+            (None, _) => {
+                profiles.push(profile);
+                (ImportOutcome::Added, Some(name.clone()))
+            }
+            (Some(_), ProfileCollision::Rename) => {
+                let new_name = unique_profile_name(profiles, name.as_str());
+                profiles.push(Profile {
+                    name: new_name.clone(),
+                    ..profile
+                });
+                (ImportOutcome::Renamed, Some(new_name))
+            }
+            (Some(i), ProfileCollision::Overwrite) => {
+                profiles[i] = profile;
+                (ImportOutcome::Overwritten, Some(name.clone()))
+            }
+            (Some(_), ProfileCollision::Skip) => (ImportOutcome::Skipped, None),
+        };
+
+        info!("Imported profile {:?}: {:?}", name, outcome);
+        for warning in &warnings {
+            warn!("{}", warning);
+        }
+        if !unknown_mods.is_empty() {
+            warn!(
+                "Profile {:?} contains mods that are not on ModLinks: {}",
+                name,
+                unknown_mods.join(", ")
             );
         }
-        _ => panic!("OS not supported."),
+        results.push(ImportedProfile {
+            name,
+            saved_as,
+            outcome,
+            unknown_mods,
+            warnings,
+        });
     }
 
+    results
+}
+
+/// Encrypt save data from a JSON file into a save slot, backing up the save it replaces
+/// # Arguments
+/// * `save_slot` - The number of the save slot to replace
+/// * `state` - The state of the application
+#[tauri::command]
+fn import_save_json(save_slot: u8, state: State<AppState>) -> Result<(), Error> {
+    let import_path = selected_path(
+        FileDialog::new()
+            .set_location("~")
+            .add_filter("JSON File", &["json"])
+            .show_open_single_file(),
+        format!("import into save slot {}", save_slot).as_str(),
+    )?;
+
+    let text = fs::read_to_string(import_path.as_path())
+        .map_err(|e| Error::Io(format!("Failed to read {}: {}", import_path.display(), e)))?;
+    let json: Value = serde_json::from_str(text.as_str()).map_err(|e| {
+        Error::Parse(format!(
+            "{} is not valid JSON: {}",
+            import_path.display(),
+            e
+        ))
+    })?;
+    let bytes = encode_save(&json)?;
+
+    let saves_path = saves_dir()?;
+    back_up_save_slot(saves_path.as_path(), save_slot, &state)?;
+    let save_path = saves_path.join(format!("user{}.dat", save_slot));
+    fs::write(save_path.as_path(), bytes)
+        .map_err(|e| Error::Io(format!("Failed to write save slot {}: {}", save_slot, e)))?;
+    info!("Imported save JSON into slot {}", save_slot);
+    Ok(())
+}
+
+/// Import a save into the game's saves folder, backing up the save it replaces
+/// # Arguments
+/// * `save_slot` - The number of the save slot to replace
+/// * `state` - The state of the application
+#[tauri::command]
+fn import_save(save_slot: u8, state: State<AppState>) -> Result<(), Error> {
+    let import_path = selected_path(
+        FileDialog::new()
+            .set_location("~")
+            .add_filter("Save file", &["dat"])
+            .show_open_single_file(),
+        format!("import into save slot {}", save_slot).as_str(),
+    )?;
+
+    let saves_path = saves_dir()?;
+    back_up_save_slot(saves_path.as_path(), save_slot, &state)?;
+    let save_path = saves_path.join(format!("user{}.dat", save_slot));
     match fs::copy(import_path, save_path) {
         Ok(_) => info!(
             "Successfully copied save file to saves folder for slot {}.",
             save_slot
         ),
-        Err(e) => error!(
-            "Failed to copy save file to saves folder for slot {}: {}.",
-            save_slot, e
-        ),
+        Err(e) => {
+            return Err(Error::Io(format!(
+                "Failed to copy save file to saves folder for slot {}: {}.",
+                save_slot, e
+            )))
+        }
     }
+
+    Ok(())
 }
 
-/// Download a mod to disk from a provided link
+/// Back up the files of a save slot before they are overwritten, then prune old backups
+/// # Arguments
+/// * `saves_path` - The folder the game reads saves from
+/// * `save_slot` - The number of the save slot
+/// * `state` - The state of the application
+fn back_up_save_slot(
+    saves_path: &Path,
+    save_slot: u8,
+    state: &State<AppState>,
+) -> Result<(), Error> {
+    let limits: SaveBackupLimits;
+    {
+        let app_state = state.0.lock().unwrap();
+        limits = app_state.settings.save_backups;
+    }
+
+    back_up_save_slots(saves_path, &[save_slot], &limits)
+}
+
+/// Back up the files of several save slots before they are overwritten, then prune old backups
+/// # Arguments
+/// * `saves_path` - The folder the game reads saves from
+/// * `save_slots` - The numbers of the save slots
+/// * `limits` - How many backups are kept
+fn back_up_save_slots(
+    saves_path: &Path,
+    save_slots: &[u8],
+    limits: &SaveBackupLimits,
+) -> Result<(), Error> {
+    let backups_path = settings_dir()?.join(SAVE_BACKUPS_FOLDER);
+    for save_slot in save_slots {
+        backup_slot(saves_path, backups_path.as_path(), *save_slot).map_err(|e| {
+            e.context(format!("Failed to back up save slot {}", save_slot).as_str())
+        })?;
+    }
+    prune_backups(backups_path.as_path(), limits);
+    Ok(())
+}
+
+/// Run an operation that blocks, such as one that waits for its turn in the download queue, on
+/// the runtime's blocking threads so that it never holds up the workers that downloads run on
+/// # Arguments
+/// * `app_handle` - The handle of the application, passed to the operation with its state
+/// * `operation` - The operation to run
+async fn run_blocking<T, F>(app_handle: AppHandle, operation: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce(&AppHandle, &State<AppState>) -> Result<T, Error> + Send + 'static,
+{
+    async_runtime::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        operation(&app_handle, &state)
+    })
+    .await
+    .map_err(|e| Error::InvalidState(format!("The operation was aborted: {}", e)))?
+}
+
+/// Run a function on the main thread, such as one that shows a dialog, and wait for its result.
+/// Must not be called from the main thread itself.
+/// # Arguments
+/// * `app_handle` - The handle of the application
+/// * `function` - The function to run
+fn on_main_thread<T, F>(app_handle: &AppHandle, function: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (result_tx, result_rx) = mpsc::channel();
+    app_handle
+        .run_on_main_thread(move || {
+            let _ = result_tx.send(function());
+        })
+        .map_err(|e| Error::InvalidState(format!("Failed to run on the main thread: {}", e)))?;
+    result_rx
+        .recv()
+        .map_err(|e| Error::InvalidState(format!("The main thread did not respond: {}", e)))
+}
+
+/// Download a mod and all of its dependencies to disk, returning the names of the dependencies
+/// that were installed or enabled along the way
 /// # Arguments
 /// * `mod_name` - The name of the mod folder to be created
 /// * `mod_version` - The downloaded mod's version
+/// * `mod_hash` - The SHA256 hash of the mod's download, as listed on ModLinks
 /// * `mod_link` - The download link of the mod
-/// * `state` - The state of the application
+/// * `app_handle` - The handle of the application, used to emit download progress
 #[tauri::command]
-fn install_mod(
+async fn install_mod(
     mod_name: String,
-    mod_version: String,
+    mod_version: ModVersion,
     mod_hash: String,
     mod_link: String,
-    state: State<AppState>,
-) {
+    app_handle: AppHandle,
+) -> Result<Vec<String>, Error> {
+    run_blocking(app_handle, move |app_handle, state| {
+        install_with_dependencies(mod_name, mod_version, mod_hash, mod_link, app_handle, state)
+    })
+    .await
+}
+
+/// Download a mod and all of its dependencies to disk, blocking until they are installed
+/// # Arguments
+/// * `mod_name` - The name of the mod folder to be created
+/// * `mod_version` - The downloaded mod's version
+/// * `mod_hash` - The SHA256 hash of the mod's download, as listed on ModLinks
+/// * `mod_link` - The download link of the mod
+/// * `app_handle` - The handle of the application, used to emit download progress
+/// * `state` - The state of the application
+fn install_with_dependencies(
+    mod_name: String,
+    mod_version: ModVersion,
+    mod_hash: String,
+    mod_link: String,
+    app_handle: &AppHandle,
+    state: &State<AppState>,
+) -> Result<Vec<String>, Error> {
     info!("Installing mod {:?}", mod_name);
-    let mut app_state = state.0.lock().unwrap();
-    (*app_state).current_download_progress = 0;
-    let mods_path = app_state.settings.mods_path.clone();
-    let mod_path: PathBuf = [mods_path.as_str(), mod_name.as_str()].iter().collect();
-    let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", mod_name.as_str()]
-        .iter()
-        .collect();
+    let mods_path: String;
+    let remote_manifests: Vec<RemoteModManifest>;
+    let installed_version: Option<ModVersion>;
+    {
+        let app_state = state.0.lock().unwrap();
+        mods_path = game_mods_path(&app_state.settings)?;
+        remote_manifests = app_state.remote_mod_links.manifests.clone();
+        installed_version = app_state
+            .settings
+            .mod_links
+            .manifests
+            .iter()
+            .find(|m| m.name == mod_name && m.installed)
+            .map(|m| m.version.clone());
+    }
+
+    let install_order = if remote_manifests.iter().any(|m| m.name == mod_name) {
+        resolve_dependencies(std::slice::from_ref(&mod_name), &remote_manifests)?
+    } else {
+        warn!(
+            "Mod {:?} is not on ModLinks, installing it without dependencies.",
+            mod_name
+        );
+        vec![mod_name.clone()]
+    };
+
+    let mut pulled_in = vec![];
+    for name in install_order {
+        let mod_path: PathBuf = [mods_path.as_str(), name.as_str()].iter().collect();
+        let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", name.as_str()]
+            .iter()
+            .collect();
+        if name == mod_name {
+            if disabled_mod_path.exists() {
+                enable_mod(name.clone(), state.clone())?;
+            }
+            let on_disk = mod_path.exists() || disabled_mod_path.exists();
+            match installed_version
+                .as_ref()
+                .map(|v| v.change_to(&mod_version))
+            {
+                Some(VersionChange::Identical) if on_disk => {
+                    info!(
+                        "Mod {:?} is already installed at version {}.",
+                        mod_name, mod_version
+                    );
+                    continue;
+                }
+                Some(VersionChange::Downgrade) => warn!(
+                    "Downgrading mod {:?} from version {} to {}.",
+                    mod_name,
+                    installed_version.as_ref().unwrap(),
+                    mod_version
+                ),
+                _ => (),
+            }
+            download_and_track(
+                name,
+                mod_version.clone(),
+                mod_link.clone(),
+                mod_hash.clone(),
+                false,
+                app_handle,
+                state,
+            )?;
+            continue;
+        }
+
+        if install_dependency(
+            &name,
+            &mod_name,
+            false,
+            &remote_manifests,
+            app_handle,
+            state,
+        )? {
+            pulled_in.push(name);
+        }
+    }
+
+    Ok(pulled_in)
+}
+
+/// Make sure a dependency of a mod is installed, downloading it if it is not. The dependency
+/// is enabled along with an enabled dependent, while a disabled dependent leaves it where it
+/// is and downloads it into the Disabled folder. Returns whether anything had to be done.
+/// # Arguments
+/// * `name` - The name of the dependency
+/// * `dependent` - The name of the mod that depends on it
+/// * `disabled` - Whether the dependent is in the Disabled folder
+/// * `remote_manifests` - The manifests fetched from ModLinks
+/// * `app_handle` - The handle of the application, used to emit download progress
+/// * `state` - The state of the application
+fn install_dependency(
+    name: &str,
+    dependent: &str,
+    disabled: bool,
+    remote_manifests: &[RemoteModManifest],
+    app_handle: &AppHandle,
+    state: &State<AppState>,
+) -> Result<bool, Error> {
+    let mods_path: String;
+    {
+        let app_state = state.0.lock().unwrap();
+        mods_path = game_mods_path(&app_state.settings)?;
+    }
+    let mod_path: PathBuf = [mods_path.as_str(), name].iter().collect();
+    let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", name].iter().collect();
     if mod_path.exists() {
-        // let out_of_date = check_for_update(mod_name.clone(), mod_version.clone()).await;
-        // if !out_of_date {
-        //     warn!("Mod {:?} already installed", mod_name);
-        //     let mut current_download_progress = CURRENT_DOWNLOAD_PROGRESS.write().await;
-        //     *current_download_progress = 100;
-        //     return Ok(());
-        // }
-        enable_mod(mod_name.clone(), state.clone());
+        info!(
+            "Dependency {:?} of {:?} is already installed.",
+            name, dependent
+        );
+        Ok(false)
+    } else if disabled_mod_path.exists() && disabled {
+        info!(
+            "Dependency {:?} of disabled mod {:?} is already installed.",
+            name, dependent
+        );
+        Ok(false)
     } else if disabled_mod_path.exists() {
-        // let out_of_date = check_for_update(mod_name.clone(), mod_version.clone()).await;
-        // if !out_of_date {
-        //     warn!("Mod {:?} already installed but is disabled, enabling it instead.", mod_name);
-        //     enable_mod(mod_name).await;
-        //     let mut current_download_progress = CURRENT_DOWNLOAD_PROGRESS.write().await;
-        //     *current_download_progress = 100;
-        //     return Ok(());
-        // } else {
-        //     uninstall_mod(mod_name.clone()).await;
-        // }
+        info!(
+            "Enabling disabled dependency {:?} of {:?}.",
+            name, dependent
+        );
+        enable_mod(name.to_string(), state.clone())?;
+        Ok(true)
+    } else {
+        info!("Installing dependency {:?} of {:?}.", name, dependent);
+        let manifest = remote_manifests
+            .iter()
+            .find(|m| m.name == name)
+            .ok_or_else(|| {
+                Error::InvalidState(format!("Mod {:?} could not be found on ModLinks.", name))
+            })?;
+        download_and_track(
+            name.to_string(),
+            manifest.version.clone(),
+            manifest.link.link.clone(),
+            manifest.link.sha256.clone(),
+            disabled,
+            app_handle,
+            state,
+        )?;
+        Ok(true)
     }
+}
 
-    let (tx, rx) = mpsc::channel();
-    let tx = tx.clone();
+/// The outcome of updating a single installed mod
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct ModUpdateResult {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Previous Version")]
+    previous_version: ModVersion,
+    #[serde(rename = "New Version")]
+    new_version: ModVersion,
+    /// Whether the mod is in the Mods folder rather than the Disabled folder
+    #[serde(rename = "Enabled")]
+    enabled: bool,
+    /// Whether the mod was asked to be updated but was already at the version on ModLinks
+    #[serde(rename = "Up To Date")]
+    up_to_date: bool,
+    #[serde(rename = "Error")]
+    error: Option<Error>,
+}
 
+/// Update installed mods to the version on ModLinks, keeping each mod in the Mods or
+/// Disabled folder it is currently in, and return the outcome for each mod
+/// # Arguments
+/// * `mod_names` - The names of the mods to update, or every outdated mod if not given
+/// * `app_handle` - The handle of the application, used to emit download progress
+#[tauri::command]
+async fn update_mods(
+    mod_names: Option<Vec<String>>,
+    app_handle: AppHandle,
+) -> Result<Vec<ModUpdateResult>, Error> {
+    run_blocking(app_handle, move |app_handle, state| {
+        update_installed_mods(mod_names, app_handle, state)
+    })
+    .await
+}
+
+/// Update installed mods to the version on ModLinks, blocking until the downloads are done
+/// # Arguments
+/// * `mod_names` - The names of the mods to update, or every outdated mod if not given
+/// * `app_handle` - The handle of the application, used to emit download progress
+/// * `state` - The state of the application
+fn update_installed_mods(
+    mod_names: Option<Vec<String>>,
+    app_handle: &AppHandle,
+    state: &State<AppState>,
+) -> Result<Vec<ModUpdateResult>, Error> {
+    let mods_path: String;
+    let remote_manifests: Vec<RemoteModManifest>;
+    let local_manifests: Vec<LocalModManifest>;
+    {
+        let app_state = state.0.lock().unwrap();
+        mods_path = game_mods_path(&app_state.settings)?;
+        remote_manifests = app_state.remote_mod_links.manifests.clone();
+        local_manifests = app_state.settings.mod_links.manifests.clone();
+    }
+
+    let mut results = vec![];
+    for local_manifest in local_manifests {
+        if !local_manifest.installed {
+            continue;
+        }
+        if let Some(mod_names) = &mod_names {
+            if !mod_names.contains(&local_manifest.name) {
+                continue;
+            }
+        }
+        let remote_manifest = match remote_manifests
+            .iter()
+            .find(|m| m.name == local_manifest.name)
+        {
+            Some(manifest) => manifest,
+            None => {
+                if mod_names.is_some() {
+                    results.push(ModUpdateResult {
+                        name: local_manifest.name.clone(),
+                        previous_version: local_manifest.version.clone(),
+                        new_version: local_manifest.version.clone(),
+                        enabled: local_manifest.enabled,
+                        up_to_date: false,
+                        error: Some(Error::InvalidState(format!(
+                            "Mod {:?} could not be found on ModLinks.",
+                            local_manifest.name
+                        ))),
+                    });
+                }
+                continue;
+            }
+        };
+        if local_manifest.version.change_to(&remote_manifest.version) != VersionChange::Upgrade {
+            // Mods that were asked for by name are reported even when there is nothing to do
+            if mod_names.is_some() {
+                info!("Mod {:?} is already up to date.", local_manifest.name);
+                results.push(ModUpdateResult {
+                    name: local_manifest.name.clone(),
+                    previous_version: local_manifest.version.clone(),
+                    new_version: local_manifest.version.clone(),
+                    enabled: local_manifest.enabled,
+                    up_to_date: true,
+                    error: None,
+                });
+            }
+            continue;
+        }
+
+        let name = local_manifest.name.clone();
+        let mod_path: PathBuf = [mods_path.as_str(), name.as_str()].iter().collect();
+        let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", name.as_str()]
+            .iter()
+            .collect();
+        let disabled = !mod_path.exists() && disabled_mod_path.exists();
+        info!(
+            "Updating mod {:?} from version {} to {}",
+            name, local_manifest.version, remote_manifest.version
+        );
+
+        // Dependencies are installed before the mod so that it is never left updated without
+        // them, and the old version is only replaced once the new one has been downloaded and
+        // extracted
+        let result = resolve_dependencies(std::slice::from_ref(&name), &remote_manifests)
+            .and_then(|install_order| {
+                for dependency in install_order.iter().filter(|d| **d != name) {
+                    install_dependency(
+                        dependency,
+                        &name,
+                        disabled,
+                        &remote_manifests,
+                        app_handle,
+                        state,
+                    )?;
+                }
+                Ok(())
+            })
+            .and_then(|_| {
+                download_and_track(
+                    name.clone(),
+                    remote_manifest.version.clone(),
+                    remote_manifest.link.link.clone(),
+                    remote_manifest.link.sha256.clone(),
+                    disabled,
+                    app_handle,
+                    state,
+                )
+            });
+        match &result {
+            Ok(_) => info!("Successfully updated mod {:?}.", name),
+            Err(e) => error!("Failed to update mod {:?}: {}", name, e),
+        }
+
+        results.push(ModUpdateResult {
+            name,
+            previous_version: local_manifest.version,
+            new_version: remote_manifest.version.clone(),
+            enabled: !disabled,
+            up_to_date: false,
+            error: result.err(),
+        });
+    }
+
+    Ok(results)
+}
+
+/// Download a single mod into the Mods or Disabled folder, blocking until it has taken its
+/// turn in the download queue and finished, and mark it as installed in settings. The state
+/// is only locked before and after the download, never while it is running.
+/// # Arguments
+/// * `mod_name` - The name of the mod to be downloaded
+/// * `mod_version` - The version of the mod being downloaded
+/// * `mod_link` - The download link of the mod
+/// * `mod_hash` - The expected SHA256 hash of the download
+/// * `disabled` - Whether to download the mod into the Disabled folder
+/// * `app_handle` - The handle of the application, used to emit download progress
+/// * `state` - The state of the application
+fn download_and_track(
+    mod_name: String,
+    mod_version: ModVersion,
+    mod_link: String,
+    mod_hash: String,
+    disabled: bool,
+    app_handle: &AppHandle,
+    state: &State<AppState>,
+) -> Result<(), Error> {
+    let mods_path: String;
+    let downloads: DownloadManager;
+    {
+        let app_state = state.0.lock().unwrap();
+        mods_path = game_mods_path(&app_state.settings)?;
+        downloads = app_state.downloads.clone();
+    }
+    let mod_path: PathBuf = if disabled {
+        [mods_path.as_str(), "Disabled", mod_name.as_str()]
+            .iter()
+            .collect()
+    } else {
+        [mods_path.as_str(), mod_name.as_str()].iter().collect()
+    };
+    let install_staging_dir: PathBuf = [mods_path.as_str(), "..", INSTALL_STAGING_FOLDER]
+        .iter()
+        .collect();
+
+    let progress_handle = app_handle.clone();
+    let mut tracker = ProgressTracker::new(mod_name.as_str(), move |progress| {
+        if let Err(e) = progress_handle.emit_all(PROGRESS_EVENT, progress.clone()) {
+            warn!("Failed to emit download progress: {}", e);
+        }
+    });
+    let ticket = downloads.enqueue(tracker.operation_id(), mod_name.as_str(), mod_hash.as_str());
+    tracker.set_phase(ProgressPhase::Queued);
+    emit_download_queue(app_handle, &downloads);
+    if let Err(e) = ticket.wait_for_turn() {
+        drop(ticket);
+        emit_download_queue(app_handle, &downloads);
+        tracker.set_phase(ProgressPhase::Cancelled);
+        return Err(e);
+    }
+    emit_download_queue(app_handle, &downloads);
+
+    info!(
+        "Downloading mod {:?} as operation {}",
+        mod_name,
+        tracker.operation_id()
+    );
+    let (result_tx, result_rx) = mpsc::channel();
     let mod_name_param = mod_name.clone();
-    app_state
-        .pool
-        .execute(move || async_runtime::block_on(download_mod(tx, mod_name_param, mod_link, mods_path)));
+    let mod_link_param = mod_link.clone();
+    let mod_hash_param = mod_hash.clone();
+    {
+        let app_state = state.0.lock().unwrap();
+        app_state.pool.execute(move || {
+            let result = async_runtime::block_on(download_mod(
+                &mut tracker,
+                &ticket,
+                mod_name_param,
+                mod_link_param,
+                mod_hash_param,
+                mod_path,
+                install_staging_dir,
+            ));
+            // The download leaves the queue before its result is sent, so that the queue
+            // emitted once the result is received no longer contains it
+            drop(ticket);
+            // The receiver only goes away if the command waiting on the download has itself failed
+            let _ = result_tx.send((result, tracker));
+        });
+    }
 
-    while app_state.current_download_progress < 100 {
-        (*app_state).current_download_progress = rx.recv().unwrap();
+    let (result, mut tracker) = result_rx
+        .recv()
+        .map_err(|_| Error::Network(format!("Download of mod {:?} was aborted.", mod_name)))?;
+    emit_download_queue(app_handle, &downloads);
+    if let Err(e) = result {
+        tracker.set_phase(if matches!(e, Error::Cancelled(_)) {
+            ProgressPhase::Cancelled
+        } else {
+            ProgressPhase::Failed
+        });
+        return Err(e);
     }
 
     {
         let mut app_state = state.0.lock().unwrap();
-        for i in 0..app_state.settings.mod_links.manifests.len() {
-            if app_state.settings.mod_links.manifests[i].name == mod_name {
-                app_state.settings.mod_links.manifests[i].installed = true;
-                app_state.settings.mod_links.manifests[i].enabled = true;
+        for manifest in app_state.settings.mod_links.manifests.iter_mut() {
+            if manifest.name == mod_name {
+                manifest.version = mod_version.clone();
+                manifest.link = ModLink {
+                    sha256: mod_hash.clone(),
+                    link: mod_link.clone(),
+                };
+                manifest.installed = true;
+                manifest.enabled = !disabled;
             }
         }
     }
+    tracker.set_phase(ProgressPhase::Done);
+
+    Ok(())
 }
 
-/// Download a mod to disk from a provided URL
+/// Emit the downloads in the queue to the frontend
 /// # Arguments
-/// * `tx` - The channel to send the download progress to
+/// * `app_handle` - The handle of the application
+/// * `downloads` - The download queue
+fn emit_download_queue(app_handle: &AppHandle, downloads: &DownloadManager) {
+    if let Err(e) = app_handle.emit_all(QUEUE_EVENT, downloads.queue()) {
+        warn!("Failed to emit download queue: {}", e);
+    }
+}
+
+/// Download a mod to disk from a provided URL, verifying its SHA256 hash before extracting it.
+/// The download is staged outside the mods folder so it can be resumed if it is interrupted,
+/// and the mod is extracted into a staging folder and checked before it replaces what is in its
+/// folder, so a failed install leaves any installed version as it was.
+/// # Arguments
+/// * `tracker` - The tracker to report the progress of the download to
+/// * `ticket` - The download's place in the download queue, checked for pauses and cancellation
 /// * `name` - The name of the mod to be downloaded
 /// * `url` - The download link of the mod
-/// * `mods_path` - The path to the mods folder
-async fn download_mod(tx: mpsc::Sender<u8>, name: String, url: String, mods_path: String) {
-    let client = reqwest::Client::new();
-    let result = client
-        .get(url.clone())
-        .send()
-        .await
-        .expect("Failed to download mod.");
-    let total_size = result
-        .content_length()
-        .ok_or(format!("Failed to get content length from {}", url))
-        .unwrap();
-    let mod_path = format!("{}/{}", mods_path, name);
-
-    if !PathBuf::from_str(mod_path.as_str()).unwrap().exists() {
-        match fs::create_dir(mod_path.clone()) {
-            Ok(_) => info!("Successfully created mod folder for {:?}.", name),
-            Err(e) => error!("Failed to create mod folder for {:?}: {}", name, e),
-        }
-    }
-
-    let extension = url.split(".").last().unwrap();
-    let download_path: String;
-    if extension == "zip" {
-        download_path = format!("{}/temp.zip", mod_path.clone());
+/// * `hash` - The expected SHA256 hash of the download, as listed on ModLinks
+/// * `mod_path` - The path to the mod's folder in the Mods or Disabled folder
+/// * `install_staging_dir` - The folder that mods are extracted into before being moved into place
+async fn download_mod(
+    tracker: &mut ProgressTracker,
+    ticket: &DownloadTicket,
+    name: String,
+    url: String,
+    hash: String,
+    mod_path: PathBuf,
+    install_staging_dir: PathBuf,
+) -> Result<(), Error> {
+    if hash.is_empty() {
+        return Err(Error::InvalidState(format!(
+            "No SHA256 hash is available to verify mod {:?}.",
+            name
+        )));
+    }
+
+    let staging_dir = settings_dir()?.join(DOWNLOADS_FOLDER);
+
+    let staged_path = match download_verified(
+        tracker,
+        ticket,
+        name.as_str(),
+        url.as_str(),
+        hash.as_str(),
+        staging_dir.as_path(),
+    )
+    .await
+    {
+        Ok(staged_path) => staged_path,
+        Err(e) => {
+            // Interrupted downloads are kept to be resumed, unless the user gave up on them
+            if matches!(e, Error::Cancelled(_)) {
+                discard_staged(staging_dir.as_path(), hash.as_str());
+            }
+            return Err(e);
+        }
+    };
+
+    tracker.set_phase(ProgressPhase::Extract);
+    let operation_id = tracker.operation_id();
+    let staged_dir = install_staging_dir.join(operation_id.to_string());
+    let backup_dir = install_staging_dir.join(format!("{}.previous", operation_id));
+    let result = extract_mod(
+        name.as_str(),
+        url.as_str(),
+        staged_path.as_path(),
+        staged_dir.as_path(),
+    )
+    .and_then(|_| validate_staged(staged_dir.as_path(), name.as_str()))
+    .and_then(|_| {
+        swap_into_place(
+            staged_dir.as_path(),
+            mod_path.as_path(),
+            backup_dir.as_path(),
+        )
+    });
+
+    match &result {
+        Ok(_) => discard_staged(staging_dir.as_path(), hash.as_str()),
+        Err(_) if staged_dir.exists() => {
+            if let Err(e) = fs::remove_dir_all(staged_dir.as_path()) {
+                error!("Failed to remove staged install of mod {:?}: {}", name, e);
+            }
+        }
+        Err(_) => (),
+    }
+    // The staging folder is only removed once no other install is using it
+    let _ = fs::remove_dir(install_staging_dir.as_path());
+
+    result
+}
+
+/// Extract a mod's download into a staging folder, or copy it there if it is not a ZIP
+/// # Arguments
+/// * `name` - The name of the mod
+/// * `url` - The download link of the mod, whose extension is that of the download
+/// * `download_path` - The path to the downloaded file
+/// * `staged_dir` - The folder to extract the mod into
+fn extract_mod(
+    name: &str,
+    url: &str,
+    download_path: &Path,
+    staged_dir: &Path,
+) -> Result<(), Error> {
+    fs::create_dir_all(staged_dir)
+        .map_err(|e| Error::Io(format!("Failed to create {}: {}", staged_dir.display(), e)))?;
+
+    let file_name = url.rsplit('/').next().unwrap_or_default();
+    if file_name.rsplit('.').next() == Some("zip") {
+        let file = File::open(download_path)
+            .map_err(|e| Error::Io(format!("Failed to open {}: {}", download_path.display(), e)))?;
+        Unzipper::new(file, staged_dir).unzip().map_err(|e| {
+            Error::Io(format!(
+                "Failed to unzip contents of {}: {}",
+                download_path.display(),
+                e
+            ))
+        })?;
+        info!("Successfully unzipped download of mod {:?}", name);
     } else {
-        download_path = format!(
-            "{}/{}",
-            mod_path.clone(),
-            url.clone().split("/").last().unwrap()
+        let file_path = staged_dir.join(file_name);
+        fs::copy(download_path, file_path.as_path())
+            .map_err(|e| Error::Io(format!("Failed to copy {}: {}", file_path.display(), e)))?;
+    }
+
+    Ok(())
+}
+
+/// Download a mod's file into the staging folder, resuming it if an earlier attempt was
+/// interrupted and downloading it again if its hash does not match. Returns the path to the
+/// staged file.
+/// # Arguments
+/// * `tracker` - The tracker to report the progress of the download to
+/// * `ticket` - The download's place in the download queue, checked for pauses and cancellation
+/// * `name` - The name of the mod to be downloaded
+/// * `url` - The download link of the mod
+/// * `hash` - The expected SHA256 hash of the download, as listed on ModLinks
+/// * `staging_dir` - The folder that downloads are staged in
+async fn download_verified(
+    tracker: &mut ProgressTracker,
+    ticket: &DownloadTicket,
+    name: &str,
+    url: &str,
+    hash: &str,
+    staging_dir: &Path,
+) -> Result<PathBuf, Error> {
+    let client = reqwest::Client::new();
+    let mut attempt = 1;
+    loop {
+        ticket.checkpoint()?;
+        let staged_path = download_staged(&client, tracker, ticket, url, hash, staging_dir)
+            .await
+            .map_err(|e| e.context(format!("Failed to download mod {:?}", name).as_str()))?;
+
+        tracker.set_phase(ProgressPhase::Verify);
+        let file_hash = digest_file(staged_path.as_path())
+            .map_err(|e| Error::Io(format!("Failed to hash {}: {}", staged_path.display(), e)))?;
+        if file_hash.to_lowercase() == hash.to_lowercase() {
+            info!(
+                "Downloaded hash of {:?} matches with that on modlinks.",
+                name
+            );
+            return Ok(staged_path);
+        }
+
+        discard_staged(staging_dir, hash);
+        if attempt >= MAX_DOWNLOAD_ATTEMPTS {
+            return Err(Error::HashMismatch(format!(
+                "SHA256 mismatch for mod {:?} after {} attempts: expected {}, got {}",
+                name, attempt, hash, file_hash
+            )));
+        }
+
+        warn!(
+            "Failed to verify SHA256 of downloaded file for mod {:?} (attempt {} of {}), re-downloading...",
+            name, attempt, MAX_DOWNLOAD_ATTEMPTS
         );
+        attempt += 1;
     }
+}
 
+/// Manually install a mod from disk. The mod is staged and checked like a downloaded mod
+/// before it replaces any installed version. Returns the name of the mod, or an empty string
+/// if a mod of that name was already installed.
+/// # Arguments
+/// * `state` - The state of the application
+#[tauri::command]
+fn manually_install_mod(state: State<AppState>) -> Result<String, Error> {
+    let mods_path: String;
     {
-        let mut file = File::create(download_path.clone()).unwrap();
-        let mut downloaded: u64 = 0;
-        let mut stream = result.bytes_stream();
-        while let Some(item) = stream.next().await {
-            let chunk = item.unwrap();
-            file.write_all(&chunk).unwrap();
-            let new = min(downloaded + (chunk.len() as u64), total_size);
-            downloaded = new;
-            tx.send((((new as f64) / (total_size as f64)) * 100.0).floor() as u8).expect("Failed to send download progress.");
-        }
-    }
-
-    /*let file_hash = digest_file(download_path.clone()).unwrap();
-    if file_hash.to_lowercase() != mod_hash.to_lowercase() {
-        error!("Failed to verify SHA256 of downloaded file for mod {:?}, re-downloading...", mod_name);
-        install_mod(mod_name.clone(), mod_version, mod_hash, mod_link.clone()).await;
-    } else {
-        info!("Downloaded hash of {:?} matches with that on modlinks.", mod_name);
-    }*/
+        let app_state = state.0.lock().unwrap();
+        mods_path = game_mods_path(&app_state.settings)?;
+    }
+    let selected_path = selected_path(
+        FileDialog::new()
+            .set_location("~")
+            .add_filter("Dynamic Link Library", &["dll"])
+            .add_filter("ZIP Archive", &["zip"])
+            .show_open_single_file(),
+        "install a mod from",
+    )?;
 
-    if extension == "zip" {
-        let file = File::open(download_path.clone()).unwrap();
-        let unzipper = Unzipper::new(file, mod_path);
-        match unzipper.unzip() {
-            Ok(_) => info!("Successfully unzipped contents of {}", download_path),
-            Err(e) => error!("Failed to unzip contents of {}: {}", download_path, e),
+    let path = Path::new(&selected_path);
+    let mod_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            Error::InvalidState(format!("{} is not a file.", selected_path.display()))
+        })?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mod_path: PathBuf = [mods_path.as_str(), mod_name.as_str()].iter().collect();
+    let install_staging_dir: PathBuf = [mods_path.as_str(), "..", INSTALL_STAGING_FOLDER]
+        .iter()
+        .collect();
+    let staged_dir = install_staging_dir.join(format!("manual-{}", mod_name));
+    let backup_dir = install_staging_dir.join(format!("manual-{}.previous", mod_name));
+
+    let result = extract_mod(
+        mod_name.as_str(),
+        file_name.as_str(),
+        path,
+        staged_dir.as_path(),
+    )
+    .and_then(|_| validate_staged(staged_dir.as_path(), mod_name.as_str()))
+    .and_then(|_| {
+        swap_into_place(
+            staged_dir.as_path(),
+            mod_path.as_path(),
+            backup_dir.as_path(),
+        )
+    });
+    if result.is_err() && staged_dir.exists() {
+        if let Err(e) = fs::remove_dir_all(staged_dir.as_path()) {
+            error!(
+                "Failed to remove staged install of mod {:?}: {}",
+                mod_name, e
+            );
         }
+    }
+    let _ = fs::remove_dir(install_staging_dir.as_path());
+    result?;
+    info!(
+        "Manually installed mod {:?} from {}",
+        mod_name,
+        path.display()
+    );
 
-        fs::remove_file(download_path).unwrap();
+    let mut app_state = state.0.lock().unwrap();
+    let manifests = &mut app_state.settings.mod_links.manifests;
+    if manifests.iter().any(|manifest| manifest.name == mod_name) {
+        return Ok("".to_string());
     }
+
+    manifests.push(LocalModManifest {
+        name: mod_name.clone(),
+        description: String::from("No description available."),
+        version: ModVersion::unknown(),
+        link: ModLink {
+            sha256: "".to_string(),
+            link: "".to_string(),
+        },
+        dependencies: ModDependencies {
+            dependencies: vec![],
+        },
+        repository: "".to_string(),
+        tags: Some(ModTags { tags: vec![] }),
+        source: "".to_string(),
+        enabled: true,
+        installed: true,
+    });
+
+    Ok(mod_name)
 }
 
-/// Manually install a mod from disk.
+/// Add the mods of one profile to another, keeping the target profile's pins where both
+/// profiles pin the same mod
 /// # Arguments
+/// * `source_profile` - The name of the profile whose mods are added
+/// * `target_profile` - The name of the profile that is added to
 /// * `state` - The state of the application
 #[tauri::command]
-fn manually_install_mod(state: State<AppState>) -> String {
+fn merge_profiles(
+    source_profile: String,
+    target_profile: String,
+    state: State<AppState>,
+) -> Result<Profile, Error> {
+    if source_profile == target_profile {
+        return Err(Error::InvalidState(
+            "A profile cannot be merged into itself.".to_string(),
+        ));
+    }
+
     let mut app_state = state.0.lock().unwrap();
-    let selected_path = FileDialog::new()
-        .set_location("~")
-        .add_filter("Dynamic Link Library", &["dll"])
-        .add_filter("ZIP Archive", &["zip"])
-        .show_open_single_file()
-        .unwrap();
-    let selected_path = match selected_path {
-        Some(path) => path,
-        None => {
-            error!("Selected path is not valid.");
-            return "".to_string();
+    let profiles = &mut app_state.settings.profiles;
+    let source = find_profile(profiles, source_profile.as_str())?.clone();
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.name == target_profile)
+        .ok_or_else(|| {
+            Error::InvalidState(format!("Profile {:?} does not exist.", target_profile))
+        })?;
+    target.merge(&source);
+
+    info!(
+        "Merged profile {:?} into {:?}",
+        source_profile, target_profile
+    );
+    Ok(target.clone())
+}
+
+/// List the backups of one or all save slots, newest first
+/// # Arguments
+/// * `save_slot` - The number of the save slot, or None for every slot
+#[tauri::command]
+fn list_save_backups(save_slot: Option<u8>) -> Result<Vec<SaveBackup>, Error> {
+    Ok(list_backups(
+        settings_dir()?.join(SAVE_BACKUPS_FOLDER).as_path(),
+        save_slot,
+    ))
+}
+
+/// Summarize the saves in save slots 1 to 4
+#[tauri::command]
+fn list_save_slots() -> Result<Vec<SaveSlot>, Error> {
+    Ok(list_slots(saves_dir()?.as_path()))
+}
+
+/// Open the local folder on the file system containing all installed mods
+/// # Arguments
+/// * `state` - The state of the application
+#[tauri::command]
+fn open_mods_folder(state: State<AppState>) -> Result<(), Error> {
+    let app_state = state.0.lock().unwrap();
+    let mods_path = game_mods_path(&app_state.settings)?;
+    info!("Mods path: {:?}", mods_path.as_str());
+    let result = match env::consts::OS {
+        "linux" => Command::new("xdg-open").arg(mods_path.as_str()).spawn(),
+        "macos" => Command::new("open").arg(mods_path.as_str()).spawn(),
+        "windows" => Command::new("explorer")
+            .arg(mods_path.replace('/', "\\"))
+            .spawn(),
+        os => {
+            return Err(Error::InvalidState(format!(
+                "Opening folders is not supported on {}.",
+                os
+            )))
         }
     };
+    result.map_err(|e| Error::Io(format!("Failed to open mods folder: {}", e)))?;
+    info!("Successfully opened mods folder.");
+    Ok(())
+}
 
-    let path = Path::new(&selected_path);
-    let mods_path = &app_state.settings.mods_path;
-    let extension = path.extension().unwrap().to_str().unwrap();
-    let mod_name = String::from(path.file_name().unwrap().to_str().unwrap())
-        .replace(format!(".{}", extension).as_str(), "");
-    let mod_path = format!("{}/{}", mods_path, mod_name);
-    let dll_path = format!("{}/{}.dll", mod_path, mod_name);
-    match fs::create_dir(Path::new(&mod_path)) {
-        Ok(_) => info!(
-            "Successfully created directory for manually installed mod {}",
+/// Open a mod's read me if it has one
+/// # Arguments
+/// * `mod_name` - The name of the mod whose readme is to be opened
+/// * `state` - The state of the application
+#[tauri::command]
+fn open_mod_read_me(mod_name: String, state: State<AppState>) -> Result<(), Error> {
+    let app_state = state.0.lock().unwrap();
+    let mods_path = game_mods_path(&app_state.settings)?;
+    let mod_path: PathBuf = [mods_path.as_str(), mod_name.as_str()].iter().collect();
+    let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", mod_name.as_str()]
+        .iter()
+        .collect();
+    let installed_path = if mod_path.exists() {
+        mod_path
+    } else if disabled_mod_path.exists() {
+        disabled_mod_path
+    } else {
+        return Err(Error::InvalidState(format!(
+            "The mod {:?} is not installed.",
             mod_name
-        ),
-        Err(e) => error!(
-            "Failed to create directory for manually installed mod {}: {}",
-            mod_name, e
-        ),
+        )));
+    };
+
+    let file_paths = fs::read_dir(installed_path.as_path()).map_err(|e| {
+        Error::Io(format!(
+            "Failed to read {}: {}",
+            installed_path.display(),
+            e
+        ))
+    })?;
+    for file_path in file_paths {
+        let path_buf = file_path?.path();
+        let path = Path::new(&path_buf);
+        let file_name_no_ext = path.file_stem().unwrap_or_default().to_string_lossy();
+        let file_extension = path.extension().unwrap_or_default().to_string_lossy();
+        if file_name_no_ext.to_lowercase() == "readme"
+            && (file_extension == "txt" || file_extension == "md")
+        {
+            open::that(path).map_err(|e| {
+                Error::Io(format!(
+                    "Failed to open read me file at {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            info!("Successfully opened read me file at {}", path.display());
+            return Ok(());
+        }
     }
 
-    if extension == "dll" {
-        match fs::copy(selected_path.clone(), dll_path) {
-            Ok(_) => info!("Successfully copied DLL from selected path to mod path for manually installed mod {}", mod_name),
-            Err(e) => error!("Failed to copy DLL from selected path to mod path for manually installed mod {}: {}", mod_name, e),
+    Ok(())
+}
+
+/// Pause the download queue, holding back queued downloads and halting running ones
+/// # Arguments
+/// * `app_handle` - The handle of the application, used to emit the download queue
+/// * `state` - The state of the application
+#[tauri::command]
+fn pause_downloads(app_handle: AppHandle, state: State<AppState>) -> Result<(), Error> {
+    let downloads = state.0.lock().unwrap().downloads.clone();
+    downloads.set_paused(true);
+    info!("Paused downloads.");
+    emit_download_queue(&app_handle, &downloads);
+    Ok(())
+}
+
+/// Delete the save backups that exceed the limits in settings, returning the number deleted
+/// # Arguments
+/// * `state` - The state of the application
+#[tauri::command]
+fn prune_save_backups(state: State<AppState>) -> Result<usize, Error> {
+    let app_state = state.0.lock().unwrap();
+    let backups_path = settings_dir()?.join(SAVE_BACKUPS_FOLDER);
+    Ok(prune_backups(backups_path.as_path(), &app_state.settings.save_backups).len())
+}
+
+/// Give a profile a new name
+/// # Arguments
+/// * `profile_name` - The current name of the profile
+/// * `new_name` - The new name of the profile
+/// * `state` - The state of the application
+#[tauri::command]
+fn rename_profile(
+    profile_name: String,
+    new_name: String,
+    state: State<AppState>,
+) -> Result<String, Error> {
+    let mut app_state = state.0.lock().unwrap();
+    let settings = &mut app_state.settings;
+    find_profile(&settings.profiles, profile_name.as_str())?;
+    let new_name = validate_profile_name(&settings.profiles, new_name.as_str())?;
+    let data_dir = profile_data_dir(profile_name.as_str())?;
+    let new_data_dir = profile_data_dir(new_name.as_str())?;
+    if new_data_dir.exists() {
+        return Err(Error::InvalidState(format!(
+            "Data for a profile named {:?} already exists at {}.",
+            new_name,
+            new_data_dir.display()
+        )));
+    }
+    if data_dir.exists() {
+        fs::rename(data_dir.as_path(), new_data_dir.as_path()).map_err(|e| {
+            Error::Io(format!(
+                "Failed to move data of profile {:?}: {}",
+                profile_name, e
+            ))
+        })?;
+    }
+    for profile in settings.profiles.iter_mut() {
+        if profile.name == profile_name {
+            profile.name = new_name.clone();
         }
-    } else if extension == "zip" {
-        let file = File::options()
-            .read(true)
-            .write(true)
-            .open(selected_path.clone())
-            .unwrap();
-        let unzipper = Unzipper::new(file, mod_path);
-        match unzipper.unzip() {
-            Ok(_) => info!(
-                "Successfully unzipped contents of manually installed mod at {}",
-                selected_path.display()
-            ),
-            Err(e) => error!(
-                "Failed to unzip contents of manually installed mod at {}: {}",
-                selected_path.display(),
-                e
-            ),
+    }
+    if settings.current_profile == profile_name {
+        settings.current_profile = new_name.clone();
+    }
+
+    info!("Renamed profile {:?} to {:?}", profile_name, new_name);
+    Ok(new_name)
+}
+
+/// Replace the files of a save slot with one of its backups, backing up the current files first
+/// # Arguments
+/// * `save_slot` - The number of the save slot
+/// * `backup_id` - The ID of the backup to be restored
+/// * `state` - The state of the application
+#[tauri::command]
+fn restore_save_backup(
+    save_slot: u8,
+    backup_id: String,
+    state: State<AppState>,
+) -> Result<(), Error> {
+    let limits: SaveBackupLimits;
+    {
+        let app_state = state.0.lock().unwrap();
+        limits = app_state.settings.save_backups;
+    }
+
+    let backups_path = settings_dir()?.join(SAVE_BACKUPS_FOLDER);
+    restore_backup(
+        saves_dir()?.as_path(),
+        backups_path.as_path(),
+        save_slot,
+        backup_id.as_str(),
+    )?;
+    prune_backups(backups_path.as_path(), &limits);
+    Ok(())
+}
+
+/// Resets a mod's settings by moving its settings files into a backup that can be restored,
+/// returning the names of the files that were moved
+/// # Arguments
+/// * `mod_name` - The name of the mod whose settings will be reset
+/// * `include_local_settings` - Whether to also reset the mod's per-save local settings
+/// * `state` - The state of the application
+#[tauri::command]
+fn reset_settings(
+    mod_name: String,
+    include_local_settings: Option<bool>,
+    state: State<AppState>,
+) -> Result<Vec<String>, Error> {
+    let mods_path: String;
+    {
+        let app_state = state.0.lock().unwrap();
+        mods_path = game_mods_path(&app_state.settings)?;
+    }
+
+    let mod_path: PathBuf = [mods_path.as_str(), mod_name.as_str()].iter().collect();
+    let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", mod_name.as_str()]
+        .iter()
+        .collect();
+    let mod_path = if mod_path.is_dir() {
+        mod_path
+    } else if disabled_mod_path.is_dir() {
+        disabled_mod_path
+    } else {
+        return Err(Error::InvalidState(format!(
+            "Mod {:?} is not installed.",
+            mod_name
+        )));
+    };
+
+    let dll_names: Vec<String> = fs::read_dir(mod_path.as_path())
+        .map_err(|e| Error::Io(format!("Failed to read {}: {}", mod_path.display(), e)))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .map(|ext| ext.eq_ignore_ascii_case("dll"))
+                .unwrap_or(false)
+        })
+        .filter_map(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .collect();
+    info!("DLL names: {:?}", dll_names);
+
+    let saves_path = saves_dir()?;
+    let files = settings_files(
+        saves_path.as_path(),
+        &dll_names,
+        include_local_settings.unwrap_or(false),
+    );
+    let reset = reset_settings_files(
+        saves_path.as_path(),
+        settings_dir()?.join(SETTINGS_BACKUPS_FOLDER).as_path(),
+        mod_name.as_str(),
+        &files,
+    )?;
+    Ok(reset.files)
+}
+
+/// Undo the most recent reset of a mod's settings, returning the names of the files restored
+#[tauri::command]
+fn restore_settings() -> Result<Vec<String>, Error> {
+    let backups_path = settings_dir()?.join(SETTINGS_BACKUPS_FOLDER);
+    match restore_last_reset(saves_dir()?.as_path(), backups_path.as_path())? {
+        Some(reset) => Ok(reset.files),
+        None => Err(Error::InvalidState(
+            "There is no reset of mod settings to undo.".to_string(),
+        )),
+    }
+}
+
+/// Resume the download queue after it was paused
+/// # Arguments
+/// * `app_handle` - The handle of the application, used to emit the download queue
+/// * `state` - The state of the application
+#[tauri::command]
+fn resume_downloads(app_handle: AppHandle, state: State<AppState>) -> Result<(), Error> {
+    let downloads = state.0.lock().unwrap().downloads.clone();
+    downloads.set_paused(false);
+    info!("Resumed downloads.");
+    emit_download_queue(&app_handle, &downloads);
+    Ok(())
+}
+
+/// Set the application's default language
+/// # Arguments
+/// * `language` - The language to set the application to
+/// * `state` - The state of the application
+#[tauri::command]
+fn set_language(language: String, state: State<AppState>) -> Result<(), Error> {
+    let mut app_state = state.0.lock().unwrap();
+    app_state.settings.language = language;
+    Ok(())
+}
+
+/// Sets the current mod profile in settings and applies it, enabling exactly the mods in the
+/// profile and their dependencies, disabling every other mod, installing any that are missing
+/// and switching pinned mods to their pinned version. If any step fails, the steps already
+/// taken are undone.
+/// # Arguments
+/// * `profile_name` - The name of the profile to be set to
+/// * `app_handle` - The handle of the application, used to emit download progress
+#[tauri::command]
+async fn set_profile(profile_name: String, app_handle: AppHandle) -> Result<ProfileChanges, Error> {
+    run_blocking(app_handle, move |app_handle, state| {
+        apply_profile(profile_name, app_handle, state)
+    })
+    .await
+}
+
+/// Set the current mod profile and apply it, blocking until any downloads are done
+/// # Arguments
+/// * `profile_name` - The name of the profile to be set to
+/// * `app_handle` - The handle of the application, used to emit download progress
+/// * `state` - The state of the application
+fn apply_profile(
+    profile_name: String,
+    app_handle: &AppHandle,
+    state: &State<AppState>,
+) -> Result<ProfileChanges, Error> {
+    let profile: Profile;
+    let mods_path: String;
+    let remote_manifests: Vec<RemoteModManifest>;
+    let local_manifests: Vec<LocalModManifest>;
+    let current_set: PathBuf;
+    let current_saves: PathBuf;
+    let backup_limits: SaveBackupLimits;
+    {
+        let app_state = state.0.lock().unwrap();
+        let current_profile = app_state.settings.current_profile.as_str();
+        current_set = global_settings_set(&app_state.settings.profiles, current_profile)?;
+        current_saves = saves_set(&app_state.settings.profiles, current_profile)?;
+        profile = app_state
+            .settings
+            .profiles
+            .iter()
+            .find(|p| p.name == profile_name)
+            .cloned()
+            .ok_or_else(|| {
+                Error::InvalidState(format!("Profile {:?} does not exist.", profile_name))
+            })?;
+        mods_path = game_mods_path(&app_state.settings)?;
+        remote_manifests = app_state.remote_mod_links.manifests.clone();
+        local_manifests = app_state.settings.mod_links.manifests.clone();
+        backup_limits = app_state.settings.save_backups;
+    }
+
+    let steps = plan_profile(
+        &profile,
+        mods_path.as_str(),
+        &remote_manifests,
+        &local_manifests,
+    )?;
+    let target_set = global_settings_set(std::slice::from_ref(&profile), profile_name.as_str())?;
+    let target_saves = saves_set(std::slice::from_ref(&profile), profile_name.as_str())?;
+    let saves_path = saves_dir()?;
+    let journal_path = settings_dir()?.join(SAVE_SWITCH_JOURNAL);
+    if current_saves != target_saves {
+        back_up_save_slots(saves_path.as_path(), &SAVE_SLOTS, &backup_limits)?;
+    }
+    switch_global_settings(
+        saves_path.as_path(),
+        current_set.as_path(),
+        target_set.as_path(),
+    )
+    .map_err(|e| e.context("Failed to switch global settings"))?;
+    if let Err(e) = switch_saves(
+        saves_path.as_path(),
+        current_saves.as_path(),
+        target_saves.as_path(),
+        journal_path.as_path(),
+    ) {
+        let e = e.context("Failed to switch saves");
+        return match switch_global_settings(
+            saves_path.as_path(),
+            target_set.as_path(),
+            current_set.as_path(),
+        ) {
+            Ok(_) => Err(e),
+            Err(undo_error) => Err(undo_error.context(
+                format!(
+                    "{}; then failed to switch global settings back",
+                    e.message()
+                )
+                .as_str(),
+            )),
+        };
+    }
+
+    info!("Applying profile {:?}: {:?}", profile_name, steps);
+    let replaced_dir = replaced_mods_dir(mods_path.as_str());
+    let mut done = vec![];
+    for step in steps {
+        match apply_profile_step(&step, app_handle, state) {
+            Ok(_) => done.push(step),
+            Err(e) => {
+                error!(
+                    "Failed to apply profile {:?}, rolling back: {}",
+                    profile_name, e
+                );
+                let mut undo_errors = vec![];
+                if let Err(e) = rollback_profile_steps(&done, state) {
+                    undo_errors.push(e.message().to_string());
+                }
+                if let Err(e) = switch_saves(
+                    saves_path.as_path(),
+                    target_saves.as_path(),
+                    current_saves.as_path(),
+                    journal_path.as_path(),
+                ) {
+                    undo_errors.push(format!("Failed to switch saves back: {}", e.message()));
+                }
+                if let Err(e) = switch_global_settings(
+                    saves_path.as_path(),
+                    target_set.as_path(),
+                    current_set.as_path(),
+                ) {
+                    undo_errors.push(format!(
+                        "Failed to switch global settings back: {}",
+                        e.message()
+                    ));
+                }
+
+                let e = e.context(format!("Failed to apply profile {:?}", profile_name).as_str());
+                if !undo_errors.is_empty() {
+                    // Replaced mods that could not be put back are left where they were kept
+                    return Err(Error::InvalidState(format!(
+                        "{}; the profile could not be fully rolled back: {}",
+                        e.message(),
+                        undo_errors.join("; ")
+                    )));
+                }
+                discard_replaced_mods(replaced_dir.as_path());
+                return Err(e);
+            }
         }
     }
+    discard_replaced_mods(replaced_dir.as_path());
 
-    (*app_state)
-        .settings
-        .mod_links
-        .manifests
-        .push(LocalModManifest {
-            name: mod_name.clone(),
-            description: String::from("No description available."),
-            version: String::from("Unknown"),
-            link: ModLink {
-                sha256: "".to_string(),
-                link: "".to_string(),
-            },
-            dependencies: ModDependencies {
-                dependencies: vec![],
-            },
-            repository: "".to_string(),
-            tags: Some(ModTags { tags: vec![] }),
-            enabled: true,
-            installed: true,
-        });
+    let mut app_state = state.0.lock().unwrap();
+    app_state.settings.current_profile = profile_name;
+    Ok(ProfileChanges::from_steps(&done))
+}
 
-    let manifests = &app_state.settings.mod_links.manifests;
-    let mut exists = false;
-    for manifest in manifests {
-        if manifest.name == mod_name {
-            exists = true;
+/// Work out the steps needed to make the Mods folder match a profile
+/// # Arguments
+/// * `profile` - The profile to be applied
+/// * `mods_path` - The path to the mods folder
+/// * `remote_manifests` - The manifests fetched from ModLinks
+/// * `local_manifests` - The manifests saved in settings
+fn plan_profile(
+    profile: &Profile,
+    mods_path: &str,
+    remote_manifests: &[RemoteModManifest],
+    local_manifests: &[LocalModManifest],
+) -> Result<Vec<ProfileStep>, Error> {
+    let enabled_mods = mod_folders(Path::new(mods_path));
+    let disabled_mods = mod_folders(Path::new(mods_path).join("Disabled").as_path());
+
+    // Mods that are not on ModLinks, such as manually installed ones, have no dependencies
+    let (listed, unlisted): (Vec<String>, Vec<String>) = profile
+        .mods
+        .iter()
+        .cloned()
+        .partition(|name| remote_manifests.iter().any(|m| &m.name == name));
+    let mut wanted = resolve_dependencies(&listed, remote_manifests)?;
+    for name in unlisted {
+        if !enabled_mods.contains(&name)
+            && !disabled_mods.contains(&name)
+            && profile.pin(name.as_str()).is_none()
+        {
+            return Err(Error::InvalidState(format!(
+                "Mod {:?} is neither installed nor on ModLinks.",
+                name
+            )));
+        }
+        wanted.push(name);
+    }
+
+    let mut steps = vec![];
+    for name in &enabled_mods {
+        if !wanted.contains(name) {
+            steps.push(ProfileStep::Disable(name.clone()));
         }
     }
 
-    if exists {
-        return "".to_string();
+    // Pins that are not enabled keep their mod installed in the Disabled folder
+    let mut kept: Vec<(String, bool)> = wanted.into_iter().map(|name| (name, true)).collect();
+    for pin in &profile.pins {
+        if !pin.enabled && !kept.iter().any(|(name, _)| name == &pin.name) {
+            kept.push((pin.name.clone(), false));
+        }
+    }
+
+    for (name, enabled) in kept {
+        let installed = enabled_mods.contains(&name) || disabled_mods.contains(&name);
+        if !installed {
+            let mut pin = match profile.pin(name.as_str()) {
+                Some(pin) => pin.clone(),
+                None => remote_manifests
+                    .iter()
+                    .find(|m| m.name == name)
+                    .map(ModPin::from)
+                    .ok_or_else(|| {
+                        Error::InvalidState(format!(
+                            "Mod {:?} could not be found on ModLinks.",
+                            name
+                        ))
+                    })?,
+            };
+            pin.enabled = enabled;
+            steps.push(ProfileStep::Install(pin));
+            continue;
+        }
+
+        if enabled && disabled_mods.contains(&name) {
+            steps.push(ProfileStep::Enable(name.clone()));
+        }
+
+        let pin = match profile.pin(name.as_str()) {
+            Some(pin) if pin.version.is_known() => pin,
+            _ => continue,
+        };
+        if let Some(manifest) = local_manifests.iter().find(|m| m.name == name) {
+            if manifest.version.change_to(&pin.version) != VersionChange::Identical {
+                steps.push(ProfileStep::Replace {
+                    previous: ModPin::from(manifest),
+                    pin: pin.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Take a single step of applying a profile
+/// # Arguments
+/// * `step` - The step to be taken
+/// * `app_handle` - The handle of the application, used to emit download progress
+/// * `state` - The state of the application
+fn apply_profile_step(
+    step: &ProfileStep,
+    app_handle: &AppHandle,
+    state: &State<AppState>,
+) -> Result<(), Error> {
+    match step {
+        ProfileStep::Enable(name) => set_mod_enabled(name, true, state),
+        ProfileStep::Disable(name) => set_mod_enabled(name, false, state),
+        ProfileStep::Install(pin) => install_pin(pin, !pin.enabled, app_handle, state),
+        ProfileStep::Replace { pin, .. } => replace_mod(pin, app_handle, state),
+    }
+}
+
+/// Undo the steps taken while applying a profile, most recent first. Replaced mods are put
+/// back from the folders they were kept in, so nothing is downloaded. Every step is undone
+/// that can be, and the steps that cannot are reported in the returned error.
+/// # Arguments
+/// * `steps` - The steps that were taken
+/// * `state` - The state of the application
+fn rollback_profile_steps(steps: &[ProfileStep], state: &State<AppState>) -> Result<(), Error> {
+    let mut failures = vec![];
+    for step in steps.iter().rev() {
+        let result = match step {
+            ProfileStep::Enable(name) => set_mod_enabled(name, false, state),
+            ProfileStep::Disable(name) => set_mod_enabled(name, true, state),
+            ProfileStep::Install(pin) => uninstall_mod(pin.name.clone(), state.clone()),
+            ProfileStep::Replace { previous, .. } => restore_replaced_mod(previous, state),
+        };
+        if let Err(e) = result {
+            error!("Failed to roll back {:?}: {}", step, e);
+            failures.push(format!("{:?}: {}", step, e.message()));
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidState(format!(
+            "Failed to undo {} steps: {}",
+            failures.len(),
+            failures.join("; ")
+        )))
+    }
+}
+
+/// Download the pinned version of a mod
+/// # Arguments
+/// * `pin` - The pinned version of the mod
+/// * `disabled` - Whether to download the mod into the Disabled folder
+/// * `app_handle` - The handle of the application, used to emit download progress
+/// * `state` - The state of the application
+fn install_pin(
+    pin: &ModPin,
+    disabled: bool,
+    app_handle: &AppHandle,
+    state: &State<AppState>,
+) -> Result<(), Error> {
+    if pin.link.is_empty() {
+        return Err(Error::InvalidState(format!(
+            "Mod {:?} version {} has no download link.",
+            pin.name, pin.version
+        )));
+    }
+
+    download_and_track(
+        pin.name.clone(),
+        pin.version.clone(),
+        pin.link.clone(),
+        pin.sha256.clone(),
+        disabled,
+        app_handle,
+        state,
+    )
+}
+
+/// Replace the installed version of a mod with a pinned version, keeping it in the
+/// Mods or Disabled folder it is currently in
+/// # Arguments
+/// * `pin` - The pinned version of the mod
+/// * `app_handle` - The handle of the application, used to emit download progress
+/// * `state` - The state of the application
+fn replace_mod(pin: &ModPin, app_handle: &AppHandle, state: &State<AppState>) -> Result<(), Error> {
+    let mods_path: String;
+    {
+        let app_state = state.0.lock().unwrap();
+        mods_path = game_mods_path(&app_state.settings)?;
+    }
+    let mod_path: PathBuf = [mods_path.as_str(), pin.name.as_str()].iter().collect();
+    let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", pin.name.as_str()]
+        .iter()
+        .collect();
+    let disabled = !mod_path.exists() && disabled_mod_path.exists();
+    let installed_path = if disabled {
+        disabled_mod_path
+    } else {
+        mod_path
+    };
+
+    // The installed version is kept until the whole profile has been applied, so that it can
+    // be put back without downloading it again
+    let kept_path = replaced_mods_dir(mods_path.as_str()).join(pin.name.as_str());
+    move_mod_folder(installed_path.as_path(), kept_path.as_path())?;
+    if let Err(e) = install_pin(pin, disabled, app_handle, state) {
+        if let Err(restore_error) = move_mod_folder(kept_path.as_path(), installed_path.as_path()) {
+            return Err(restore_error.context(
+                format!(
+                    "{}; then failed to put back the installed version",
+                    e.message()
+                )
+                .as_str(),
+            ));
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Put back the version of a mod that was replaced while applying a profile, from the folder
+/// it was kept in, and record it as installed in settings
+/// # Arguments
+/// * `previous` - The version of the mod that was replaced
+/// * `state` - The state of the application
+fn restore_replaced_mod(previous: &ModPin, state: &State<AppState>) -> Result<(), Error> {
+    let mut app_state = state.0.lock().unwrap();
+    let mods_path = game_mods_path(&app_state.settings)?;
+    let mod_path: PathBuf = [mods_path.as_str(), previous.name.as_str()]
+        .iter()
+        .collect();
+    let disabled_mod_path: PathBuf = [mods_path.as_str(), "Disabled", previous.name.as_str()]
+        .iter()
+        .collect();
+    let installed_path = if !mod_path.exists() && disabled_mod_path.exists() {
+        disabled_mod_path
+    } else {
+        mod_path
+    };
+    let kept_path = replaced_mods_dir(mods_path.as_str()).join(previous.name.as_str());
+    if !kept_path.exists() {
+        return Err(Error::InvalidState(format!(
+            "The replaced version of mod {:?} was not kept.",
+            previous.name
+        )));
+    }
+
+    if installed_path.exists() {
+        fs::remove_dir_all(installed_path.as_path()).map_err(|e| {
+            Error::Io(format!(
+                "Failed to remove {}: {}",
+                installed_path.display(),
+                e
+            ))
+        })?;
+    }
+    move_mod_folder(kept_path.as_path(), installed_path.as_path())?;
+    for manifest in app_state.settings.mod_links.manifests.iter_mut() {
+        if manifest.name == previous.name {
+            manifest.version = previous.version.clone();
+            manifest.link = ModLink {
+                sha256: previous.sha256.clone(),
+                link: previous.link.clone(),
+            };
+        }
+    }
+
+    info!(
+        "Put back version {} of mod {:?}",
+        previous.version, previous.name
+    );
+    Ok(())
+}
+
+/// Get the folder that mods replaced while applying a profile are kept in
+/// # Arguments
+/// * `mods_path` - The path to the Mods folder
+fn replaced_mods_dir(mods_path: &str) -> PathBuf {
+    [
+        mods_path,
+        "..",
+        INSTALL_STAGING_FOLDER,
+        REPLACED_MODS_FOLDER,
+    ]
+    .iter()
+    .collect()
+}
+
+/// Move a mod's folder, replacing whatever is at the destination
+/// # Arguments
+/// * `from` - The folder to be moved
+/// * `to` - Where the folder is moved to
+fn move_mod_folder(from: &Path, to: &Path) -> Result<(), Error> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| Error::Io(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+    if to.exists() {
+        fs::remove_dir_all(to)
+            .map_err(|e| Error::Io(format!("Failed to remove {}: {}", to.display(), e)))?;
     }
-
-    mod_name
+    fs::rename(from, to).map_err(|e| {
+        Error::Io(format!(
+            "Failed to move {} to {}: {}",
+            from.display(),
+            to.display(),
+            e
+        ))
+    })
 }
 
-/// Open the local folder on the file system containing all installed mods
+/// Delete the mods that were kept while applying a profile, once they are no longer needed
 /// # Arguments
-/// * `state` - The state of the application
-#[tauri::command]
-fn open_mods_folder(state: State<AppState>) {
-    let app_state = state.0.lock().unwrap();
-    let mods_path = &app_state.settings.mods_path;
-    info!("Mods path: {:?}", &mods_path.as_str());
-    match env::consts::OS {
-        "linux" => match Command::new("xdg-open").arg(&mods_path.as_str()).spawn() {
-            Ok(_) => info!("Successfully opened mods folder."),
-            Err(e) => error!("Failed to open mods folder: {}", e),
-        },
-        "mac" => match Command::new("open").arg(&mods_path.as_str()).spawn() {
-            Ok(_) => info!("Successfully opened mods folder."),
-            Err(e) => error!("Failed to open mods folder: {}", e),
-        },
-        "windows" => {
-            match Command::new("explorer")
-                .arg(str::replace(&mods_path.as_str(), "/", "\\"))
-                .spawn()
-            {
-                Ok(_) => info!("Successfully opened mods folder."),
-                Err(e) => error!("Failed to open mods folder: {}", e),
-            }
-        }
-        _ => panic!("OS not supported"),
-    };
+/// * `replaced_dir` - The folder the replaced mods were kept in
+fn discard_replaced_mods(replaced_dir: &Path) {
+    if !replaced_dir.exists() {
+        return;
+    }
+    match fs::remove_dir_all(replaced_dir) {
+        Ok(_) => info!("Deleted the mods replaced by the profile"),
+        Err(e) => warn!("Failed to delete {}: {}", replaced_dir.display(), e),
+    }
+    if let Some(staging_dir) = replaced_dir.parent() {
+        // The staging folder is only removed once no other install is using it
+        let _ = fs::remove_dir(staging_dir);
+    }
 }
 
-/// Open a mod's read me if it has one
+/// Create a profile from the mods that are currently in the Mods folder, including manually
+/// installed mods, and save it to settings
 /// # Arguments
-/// * `mod_name` - The name of the mod whose readme is to be opened
+/// * `profile_name` - The name of the new profile
+/// * `pin_versions` - Whether to pin every installed mod to its installed version
 /// * `state` - The state of the application
 #[tauri::command]
-fn open_mod_read_me(mod_name: String, state: State<AppState>) {
-    let app_state = state.0.lock().unwrap();
-    let mods_path = &app_state.settings.mods_path;
-    let mod_path = format!("{}/{}", mods_path, mod_name);
-    let disabled_mod_path = format!("{}/Disabled/{}", mods_path, mod_name);
-    let mut file_paths: Option<ReadDir> = None;
-    if PathBuf::from_str(mod_path.as_str()).unwrap().exists() {
-        file_paths = Some(fs::read_dir(mod_path).unwrap());
-    } else if PathBuf::from_str(disabled_mod_path.as_str())
-        .unwrap()
-        .exists()
-    {
-        file_paths = Some(fs::read_dir(disabled_mod_path).unwrap());
+fn snapshot_profile(
+    profile_name: String,
+    pin_versions: bool,
+    state: State<AppState>,
+) -> Result<Profile, Error> {
+    let mut app_state = state.0.lock().unwrap();
+    let profile_name = validate_profile_name(&app_state.settings.profiles, profile_name.as_str())?;
+    let mods_path = PathBuf::from(game_mods_path(&app_state.settings)?);
+    if !mods_path.exists() {
+        return Err(Error::MissingGamePath(format!(
+            "Mods folder {:?} does not exist.",
+            mods_path
+        )));
     }
 
-    let file_paths = match file_paths {
-        Some(value) => value,
-        None => {
-            error!("The mod {:?} is not installed.", mod_name);
-            return;
-        }
+    let manifests = &app_state.settings.mod_links.manifests;
+    let is_mod = |folder: &Path| {
+        let name = folder.file_name().unwrap_or_default().to_string_lossy();
+        manifests.iter().any(|m| m.name == name) || contains_dll(folder)
     };
+    let enabled_mods: Vec<String> = mod_folders(mods_path.as_path())
+        .into_iter()
+        .filter(|name| is_mod(mods_path.join(name).as_path()))
+        .collect();
+    let disabled_path = mods_path.join("Disabled");
+    let disabled_mods: Vec<String> = mod_folders(disabled_path.as_path())
+        .into_iter()
+        .filter(|name| !enabled_mods.contains(name))
+        .filter(|name| is_mod(disabled_path.join(name).as_path()))
+        .collect();
 
-    for file_path in file_paths {
-        let path_buf = file_path.unwrap().path();
-        let path = Path::new(&path_buf);
-        let file_name = String::from(path.file_name().unwrap().to_str().unwrap());
-        let file_extension = path.extension().unwrap().to_str().unwrap();
-        let file_name_no_ext = file_name.replace(format!(".{}", file_extension).as_str(), "");
-        if file_name_no_ext.to_lowercase() == "readme"
-            && (file_extension == "txt" || file_extension == "md")
-        {
-            match open::that(path) {
-                Ok(_) => info!("Successfully opened read me file at {}", path.display()),
-                Err(e) => error!("Failed to open read me file at {}: {}", path.display(), e),
+    let mut pins = vec![];
+    if pin_versions {
+        let installed = enabled_mods
+            .iter()
+            .map(|name| (name, true))
+            .chain(disabled_mods.iter().map(|name| (name, false)));
+        for (name, enabled) in installed {
+            // Manually installed mods have no known version or download link to pin
+            match manifests
+                .iter()
+                .find(|m| &m.name == name && !m.link.link.is_empty())
+            {
+                Some(manifest) => pins.push(ModPin {
+                    enabled,
+                    ..ModPin::from(manifest)
+                }),
+                None => info!("Not pinning mod {:?} as it was installed manually.", name),
             }
-            return;
         }
     }
+
+    let profile = Profile {
+        name: profile_name,
+        mods: enabled_mods,
+        pins,
+        global_settings: false,
+        saves: false,
+    };
+    info!(
+        "Created profile {:?} from the {} mods in the Mods folder.",
+        profile.name,
+        profile.mods.len()
+    );
+    app_state.settings.profiles.push(profile.clone());
+    Ok(profile)
 }
 
-/// Resets a mod's global settings
+/// Save the mod global settings currently in the saves folder as the current profile's own set,
+/// returning the number of settings files saved
 /// # Arguments
-/// * `mod_name` - The name of the mod whose global settings will be reset
 /// * `state` - The state of the application
 #[tauri::command]
-fn reset_settings(mod_name: String, state: State<AppState>) {
-    let app_state = state.0.lock().unwrap();
-    let mods_path = &app_state.settings.mods_path;
-    let mod_path = format!("{}/{}", mods_path, mod_name);
-    let file_paths = fs::read_dir(mod_path).unwrap();
-    let base_dir = BaseDirs::new().unwrap();
-    for file_path in file_paths {
-        let path_buf = file_path.unwrap().path();
-        let path = Path::new(&path_buf);
-        let file_name = String::from(path.file_name().unwrap().to_str().unwrap());
-        let file_extension = path.extension().unwrap().to_str().unwrap();
-        let saves_path: String;
-        let dll_name: String;
-        if file_extension == "dll" {
-            dll_name = file_name.replace(format!(".{}", file_extension).as_str(), "");
-            match env::consts::OS {
-                "linux" => {
-                    saves_path = format!(
-                        "{}/unity3d/Team Cherry/Hollow Knight",
-                        base_dir.config_dir().display()
-                    );
-                }
-                "mac" => {
-                    saves_path = format!(
-                        "{}/unity.Team Cherry.Hollow Knight",
-                        base_dir.data_dir().display()
-                    );
-                }
-                "windows" => {
-                    saves_path = format!(
-                        "{}/../LocalLow/Team Cherry/Hollow Knight",
-                        base_dir.data_dir().display()
-                    );
-                }
-                _ => panic!("OS not supported."),
-            }
-        } else {
-            continue;
-        }
-
-        info!("DLL name: {:?}", dll_name);
-
-        let data_paths = fs::read_dir(saves_path).unwrap();
-        for data_path in data_paths {
-            let path = data_path.unwrap().path();
-            let data_name = String::from(Path::new(&path).file_name().unwrap().to_str().unwrap());
-            info!("Data name: {:?}", data_name);
-            if data_name == format!("{}.GlobalSettings.json", dll_name) {
-                match fs::remove_file(Path::new(&path)) {
-                    Ok(_) => info!("Successfully deleted global settings for {}", mod_name),
-                    Err(e) => error!("Failed to delete global settings for {}: {}", mod_name, e),
-                }
-            }
-        }
+fn snapshot_global_settings(state: State<AppState>) -> Result<usize, Error> {
+    let mut app_state = state.0.lock().unwrap();
+    let settings = &mut app_state.settings;
+    let profile_name = settings.current_profile.clone();
+    let profile = settings
+        .profiles
+        .iter_mut()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| Error::InvalidState("No profile is currently active.".to_string()))?;
+
+    let saves_path = saves_dir()?;
+    let profile_set = profile_data_dir(profile_name.as_str())?.join(GLOBAL_SETTINGS_FOLDER);
+    let count = copy_global_settings(saves_path.as_path(), profile_set.as_path())?;
+
+    // The settings in use until now were the shared ones, so keep them for other profiles
+    if !profile.global_settings {
+        let shared_set = shared_data_dir()?.join(GLOBAL_SETTINGS_FOLDER);
+        copy_global_settings(saves_path.as_path(), shared_set.as_path())?;
+        profile.global_settings = true;
     }
+
+    info!(
+        "Saved {} global settings files to profile {:?}",
+        count, profile_name
+    );
+    Ok(count)
 }
 
-/// Set the application's default language
+/// Give a profile its own set of save files, which are swapped into the saves folder whenever
+/// the profile is switched to
 /// # Arguments
-/// * `language` - The language to set the application to
+/// * `profile_name` - The name of the profile
+/// * `copy_current_saves` - Whether to start with a copy of the current saves rather than none
 /// * `state` - The state of the application
 #[tauri::command]
-fn set_language(language: String, state: State<AppState>) {
+fn isolate_profile_saves(
+    profile_name: String,
+    copy_current_saves: bool,
+    state: State<AppState>,
+) -> Result<usize, Error> {
     let mut app_state = state.0.lock().unwrap();
-    app_state.settings.language = language;
+    let settings = &mut app_state.settings;
+    let backup_limits = settings.save_backups;
+    let is_current = settings.current_profile == profile_name;
+    let profile = settings
+        .profiles
+        .iter_mut()
+        .find(|p| p.name == profile_name)
+        .ok_or_else(|| {
+            Error::InvalidState(format!("Profile {:?} does not exist.", profile_name))
+        })?;
+    if profile.saves {
+        return Err(Error::InvalidState(format!(
+            "Profile {:?} already has its own saves.",
+            profile_name
+        )));
+    }
+    if read_journal(settings_dir()?.join(SAVE_SWITCH_JOURNAL).as_path()).is_some() {
+        return Err(Error::InvalidState(
+            "A previous switch of saves was interrupted and must be recovered first.".to_string(),
+        ));
+    }
+
+    let saves_path = saves_dir()?;
+    let profile_saves = profile_data_dir(profile_name.as_str())?.join(SAVES_FOLDER);
+    let empty_dir = profile_data_dir(profile_name.as_str())?.join(EMPTY_FOLDER);
+    let source = if copy_current_saves {
+        saves_path.as_path()
+    } else {
+        empty_dir.as_path()
+    };
+    let count = replace_files(source, profile_saves.as_path(), is_save_file)?;
+
+    // The saves in use belong to the shared set, so keep them before handing the saves folder
+    // over to the profile
+    if is_current {
+        back_up_save_slots(saves_path.as_path(), &SAVE_SLOTS, &backup_limits)?;
+        let shared_saves = shared_data_dir()?.join(SAVES_FOLDER);
+        replace_files(saves_path.as_path(), shared_saves.as_path(), is_save_file)?;
+        replace_files(profile_saves.as_path(), saves_path.as_path(), is_save_file)?;
+    }
+
+    profile.saves = true;
+    info!(
+        "Profile {:?} now has its own saves ({} copied).",
+        profile_name, count
+    );
+    Ok(count)
 }
 
-/// Sets the current mod profile in settings
+/// Undo a switch of saves between profiles that was interrupted, putting back the saves that
+/// were in use before the switch. Returns whether there was a switch to undo.
 /// # Arguments
-/// * `profile_name` - The name of the profile to be set to
 /// * `state` - The state of the application
 #[tauri::command]
-fn set_profile(profile_name: String, state: State<AppState>) {
-    let mut app_state = state.0.lock().unwrap();
-    app_state.settings.current_profile = profile_name;
+fn recover_saves_switch(state: State<AppState>) -> Result<bool, Error> {
+    let _app_state = state.0.lock().unwrap();
+    let journal_path = settings_dir()?.join(SAVE_SWITCH_JOURNAL);
+    match recover_saves(saves_dir()?.as_path(), journal_path.as_path())? {
+        Some(journal) => {
+            info!(
+                "Restored saves from {} after an interrupted switch to {}",
+                journal.from.display(),
+                journal.to.display()
+            );
+            Ok(true)
+        }
+        None => Ok(false),
+    }
 }
 
 /// Set the global theme
@@ -1099,82 +3025,89 @@ fn set_profile(profile_name: String, state: State<AppState>) {
 /// * `theme_name` - The name of theme to be set to
 /// * `state` - The state of the application
 #[tauri::command]
-fn set_theme(theme_name: String, state: State<AppState>) {
+fn set_theme(theme_name: String, state: State<AppState>) -> Result<(), Error> {
     let mut app_state = state.0.lock().unwrap();
     app_state.settings.theme = theme_name;
+    Ok(())
 }
 
-/// Toggles the Modding API and returns whether it has been toggled on or off
+/// Toggles the Modding API and returns whether it has been toggled on or off, along with
+/// whether ApiLinks had to be loaded from the cache if the API was downloaded
 /// # Arguments
-/// * `state` - The state of the application
+/// * `app_handle` - The handle of the application
 #[tauri::command]
-fn toggle_api(state: State<AppState>) -> bool {
+async fn toggle_api(app_handle: AppHandle) -> Result<ApiToggle, Error> {
+    run_blocking(app_handle, |_, state| toggle_installed_api(state)).await
+}
+
+/// Toggle the Modding API, blocking until it has been downloaded if it is not installed
+/// # Arguments
+/// * `state` - The state of the application
+fn toggle_installed_api(state: &State<AppState>) -> Result<ApiToggle, Error> {
     let mods_path: String;
+    let sources: Sources;
     {
         let app_state = state.0.lock().unwrap();
-        mods_path = app_state.settings.mods_path.clone();
+        mods_path = game_mods_path(&app_state.settings)?;
+        sources = app_state.settings.sources.clone();
     }
     let managed_path: PathBuf = [mods_path.as_str(), ".."].iter().collect();
-    let assembly: PathBuf = [managed_path.to_str().unwrap(), "Assembly-CSharp.dll"]
-        .iter()
-        .collect();
-    let vanilla_assembly: PathBuf = [
-        managed_path.to_str().unwrap(),
-        "Assembly-CSharp.dll.vanilla",
-    ]
-    .iter()
-    .collect();
-    let modded_assembly: PathBuf = [managed_path.to_str().unwrap(), "Assembly-CSharp.dll.modded"]
-        .iter()
-        .collect();
-    if vanilla_assembly.exists() && !modded_assembly.exists() {
-        // Disable the Modding API
-        match fs::rename(assembly.clone(), modded_assembly) {
-            Ok(_) => info!("Successfully renamed Assembly-CSharp to modded assembly backup."),
-            Err(e) => error!(
-                "Failed to rename Assembly-CSharp to modded assembly backup: {}",
-                e
-            ),
-        }
+    let assembly = managed_path.join("Assembly-CSharp.dll");
+    let vanilla_assembly = managed_path.join("Assembly-CSharp.dll.vanilla");
+    let modded_assembly = managed_path.join("Assembly-CSharp.dll.modded");
+    match (vanilla_assembly.exists(), modded_assembly.exists()) {
+        (true, false) => {
+            // Disable the Modding API
+            fs::rename(assembly.as_path(), modded_assembly).map_err(|e| {
+                Error::Io(format!(
+                    "Failed to rename Assembly-CSharp to modded assembly backup: {}",
+                    e
+                ))
+            })?;
+            info!("Successfully renamed Assembly-CSharp to modded assembly backup.");
 
-        match fs::rename(vanilla_assembly, assembly) {
-            Ok(_) => info!("Successfully replaced modded Assembly-CSharp with vanilla assembly."),
-            Err(e) => error!(
-                "Failed to replace modded Assembly-CSharp with vanilla assembly: {}",
-                e
-            ),
-        }
+            fs::rename(vanilla_assembly, assembly).map_err(|e| {
+                Error::Io(format!(
+                    "Failed to replace modded Assembly-CSharp with vanilla assembly: {}",
+                    e
+                ))
+            })?;
+            info!("Successfully replaced modded Assembly-CSharp with vanilla assembly.");
 
-        return false;
-    } else if modded_assembly.exists() && !vanilla_assembly.exists() {
-        // Enable the Modding API
-        match fs::rename(assembly.clone(), vanilla_assembly) {
-            Ok(_) => info!("Successfully renamed Assembly-CSharp to modded assembly backup."),
-            Err(e) => error!(
-                "Failed to rename Assembly-CSharp to modded assembly backup: {}",
-                e
-            ),
+            Ok((false, None))
         }
+        (false, true) => {
+            // Enable the Modding API
+            fs::rename(assembly.as_path(), vanilla_assembly).map_err(|e| {
+                Error::Io(format!(
+                    "Failed to rename Assembly-CSharp to vanilla assembly backup: {}",
+                    e
+                ))
+            })?;
+            info!("Successfully renamed Assembly-CSharp to vanilla assembly backup.");
 
-        match fs::rename(modded_assembly, assembly) {
-            Ok(_) => info!("Successfully replaced vanilla Assembly-CSharp with modded assembly."),
-            Err(e) => error!(
-                "Failed to replace vanilla Assembly-CSharp with modded assembly: {}",
-                e
-            ),
-        }
+            fs::rename(modded_assembly, assembly).map_err(|e| {
+                Error::Io(format!(
+                    "Failed to replace vanilla Assembly-CSharp with modded assembly: {}",
+                    e
+                ))
+            })?;
+            info!("Successfully replaced vanilla Assembly-CSharp with modded assembly.");
 
-        return true;
-    } else if !modded_assembly.exists() && !vanilla_assembly.exists() {
-        warn!("Neither the modded or vanilla assembly backups exists, downloading API.");
-        let app_state = state.0.lock().unwrap();
-        app_state.pool.execute(move || install_api(mods_path));
-        return true;
-    } else if modded_assembly.exists() && vanilla_assembly.exists() {
-        panic!("Somehow, both assembly backups exist.");
+            Ok((true, None))
+        }
+        (false, false) => {
+            warn!("Neither the modded or vanilla assembly backups exists, downloading API.");
+            let (api_version, cache_status) = install_api(mods_path, sources)?;
+            let mut app_state = state.0.lock().unwrap();
+            app_state.settings.api_version = api_version;
+            Ok((true, Some(cache_status)))
+        }
+        (true, true) => Err(Error::InvalidState(
+            "Both the vanilla and modded assembly backups exist, so it is unclear which assembly is in use."
+                .to_string(),
+        )),
     }
-
-    panic!("Some other error has occurred.");
 }
 
 /// Removes a mod folder from disk
@@ -1182,11 +3115,11 @@ fn toggle_api(state: State<AppState>) -> bool {
 /// * `mod_name` - The name of the mod folder
 /// * `state` - The state of the application
 #[tauri::command]
-fn uninstall_mod(mod_name: String, state: State<AppState>) {
+fn uninstall_mod(mod_name: String, state: State<AppState>) -> Result<(), Error> {
     info!("Uninstalling mod {:?}", mod_name);
     {
         let app_state = state.0.lock().unwrap();
-        let mods_path = &app_state.settings.mods_path;
+        let mods_path = game_mods_path(&app_state.settings)?;
         let mod_path: PathBuf = [mods_path.to_string(), mod_name.clone()].iter().collect();
         let disabled_mod_path: PathBuf = [
             mods_path.to_string(),
@@ -1195,26 +3128,21 @@ fn uninstall_mod(mod_name: String, state: State<AppState>) {
         ]
         .iter()
         .collect();
-        if mod_path.exists() {
-            match fs::remove_dir_all(mod_path.as_path()) {
-                Ok(_) => info!("Successfully removed all contents for {}", mod_name),
-                Err(e) => error!(
-                    "Failed to remove mod directory {:?}: {}",
-                    mod_path.to_str().unwrap(),
-                    e
-                ),
-            }
-        } else if disabled_mod_path.exists() {
-            match fs::remove_dir_all(disabled_mod_path.as_path()) {
-                Ok(_) => info!("Successfully removed all contents for {}", mod_name),
-                Err(e) => error!(
+        if mod_path.exists() || disabled_mod_path.exists() {
+            let installed_path = if mod_path.exists() {
+                mod_path
+            } else {
+                disabled_mod_path
+            };
+            fs::remove_dir_all(installed_path.as_path()).map_err(|e| {
+                Error::Io(format!(
                     "Failed to remove mod directory {:?}: {}",
-                    disabled_mod_path.to_str().unwrap(),
-                    e
-                ),
-            }
+                    installed_path, e
+                ))
+            })?;
+            info!("Successfully removed all contents for {}", mod_name);
         } else {
-            warn!("Path {:?} does not exist.", mod_path.to_str().unwrap());
+            warn!("Path {:?} does not exist.", mod_path);
         }
     }
 
@@ -1225,36 +3153,36 @@ fn uninstall_mod(mod_name: String, state: State<AppState>) {
             manifests = app_state.settings.mod_links.manifests.clone();
         }
         let mut app_state = state.0.lock().unwrap();
-        for i in 0..manifests.len() {
-            if manifests[i].name == mod_name {
+        for (i, manifest) in manifests.iter().enumerate() {
+            if manifest.name == mod_name {
                 app_state.settings.mod_links.manifests[i].installed = false;
                 app_state.settings.mod_links.manifests[i].enabled = false;
             }
         }
     }
+
+    Ok(())
 }
 
 /// Automatically detect the path to Hollow Knight executable, else prompt the user to select its path.
 /// # Arguments
 /// * `state` - The state of the application
-fn auto_detect(state: &AppState) {
+fn auto_detect(state: &AppState) -> Result<(), Error> {
     {
         let app_state = state.0.lock().unwrap();
-        if app_state.settings.mods_path != "".to_string() {
-            return;
+        if !app_state.settings.mods_path.is_empty() {
+            return Ok(());
         }
     }
 
     match env::consts::OS {
-        "linux" | "mac" => {
+        "linux" | "macos" => {
             let mut app_state = state.0.lock().unwrap();
-            match STATIC_PATHS.into_iter().find(|path| {
-                let base_dir = BaseDirs::new().unwrap();
-                let path_buf: PathBuf = [base_dir.data_dir().to_str().unwrap(), path]
-                    .iter()
-                    .collect();
-                path_buf.exists()
-            }) {
+            let data_dir = base_dirs()?.data_dir().to_path_buf();
+            match STATIC_PATHS
+                .into_iter()
+                .find(|path| data_dir.join(path).exists())
+            {
                 Some(game_path) => {
                     let confirm = MessageDialog::new()
                         .set_type(MessageType::Info)
@@ -1264,28 +3192,26 @@ fn auto_detect(state: &AppState) {
                             game_path
                         ))
                         .show_confirm()
-                        .unwrap();
+                        .map_err(|e| Error::Io(format!("Failed to open message dialog: {}", e)))?;
                     if confirm {
                         match SUFFIXES.into_iter().find(|suffix| {
                             let path_buf: PathBuf = [game_path, suffix].iter().collect();
                             path_buf.exists()
                         }) {
                             Some(suffix) => {
-                                let base_dir = BaseDirs::new().unwrap();
                                 app_state.settings.mods_path = format!(
                                     "{}/{}/{}/Mods",
-                                    base_dir.data_dir().to_str().unwrap(),
+                                    path_str(data_dir.as_path())?,
                                     game_path,
                                     suffix
-                                )
-                                .to_string();
+                                );
                             }
                             None => {
                                 error!("No managed path exists.");
                             }
                         }
                     } else {
-                        select_game_path(app_state);
+                        select_game_path(app_state)?;
                     }
                 }
                 None => {
@@ -1297,8 +3223,8 @@ fn auto_detect(state: &AppState) {
                             Please select the folder that contains your Hollow Knight executable.",
                         )
                         .show_alert()
-                        .unwrap();
-                    select_game_path(app_state)
+                        .map_err(|e| Error::Io(format!("Failed to open message dialog: {}", e)))?;
+                    select_game_path(app_state)?;
                 }
             }
         }
@@ -1306,10 +3232,7 @@ fn auto_detect(state: &AppState) {
             let mut app_state = state.0.lock().unwrap();
             let mut drive_letter: String = String::from("C:/");
             for i in 65u8..=90 {
-                if PathBuf::from_str(format!("{}:/", i).as_str())
-                    .unwrap()
-                    .exists()
-                {
+                if PathBuf::from(format!("{}:/", i)).exists() {
                     drive_letter = format!("{}:/", i);
                 }
             }
@@ -1317,10 +3240,7 @@ fn auto_detect(state: &AppState) {
                 let path_buf: PathBuf = [drive_letter.to_string(), path.to_string()]
                     .iter()
                     .collect();
-                info!(
-                    "Checking if path {} exists",
-                    path_buf.clone().into_os_string().into_string().unwrap()
-                );
+                info!("Checking if path {} exists", path_buf.display());
                 path_buf.exists()
             }) {
                 Some(game_path) => {
@@ -1333,15 +3253,12 @@ fn auto_detect(state: &AppState) {
                             game_path
                         ))
                         .show_confirm()
-                        .unwrap();
+                        .map_err(|e| Error::Io(format!("Failed to open message dialog: {}", e)))?;
                     if confirm {
                         match SUFFIXES.into_iter().find(|suffix| {
                             let path_buf: PathBuf =
                                 [drive_letter.as_str(), game_path, suffix].iter().collect();
-                            info!(
-                                "Checking managed path: {}",
-                                path_buf.clone().into_os_string().into_string().unwrap()
-                            );
+                            info!("Checking managed path: {}", path_buf.display());
                             path_buf.exists()
                         }) {
                             Some(suffix) => {
@@ -1355,36 +3272,40 @@ fn auto_detect(state: &AppState) {
                             None => error!("No managed path exists."),
                         }
                     } else {
-                        select_game_path(app_state);
+                        select_game_path(app_state)?;
                     }
                 }
-                None => select_game_path(app_state),
+                None => select_game_path(app_state)?,
             }
         }
-        _ => panic!("OS not supported"),
+        os => {
+            return Err(Error::InvalidState(format!(
+                "Detecting the game path is not supported on {}.",
+                os
+            )))
+        }
     }
 
     {
         let app_state = state.0.lock().unwrap();
         let mods_path = &app_state.settings.mods_path;
-        if !PathBuf::from_str(mods_path.as_str()).unwrap().exists() {
+        if !Path::new(mods_path.as_str()).exists() {
             match fs::create_dir(mods_path.as_str()) {
                 Ok(_) => info!("Successfully created mods directory."),
                 Err(e) => error!("Error creating mods folder: {}", e),
             }
         }
     }
+
+    Ok(())
 }
 
 /// Load the settings JSON file into the settings object, or create the file if it does not exist
 /// and open the log file
 /// # Arguments
 /// * `state` - The state of the application
-fn check_settings(state: &AppState) {
-    let base_dir = BaseDirs::new().unwrap();
-    let settings_dir: PathBuf = [base_dir.data_dir().to_str().unwrap(), SETTINGS_FOLDER]
-        .iter()
-        .collect();
+fn check_settings(state: &AppState) -> Result<(), Error> {
+    let settings_dir = settings_dir()?;
     if !settings_dir.exists() {
         match fs::create_dir(settings_dir.as_path()) {
             Ok(_) => info!("Created settings and log directory"),
@@ -1392,28 +3313,101 @@ fn check_settings(state: &AppState) {
         }
     }
 
-    let settings_string = settings_dir.to_str().unwrap();
-    let log_path = format!("{}/Log.txt", settings_string);
-    match simple_logging::log_to_file(log_path.as_str(), LevelFilter::Info) {
-        Ok(_) => info!("Opened logger at: {}", log_path.as_str()),
+    let log_path = settings_dir.join("Log.txt");
+    match simple_logging::log_to_file(log_path.as_path(), LevelFilter::Info) {
+        Ok(_) => info!("Opened logger at: {}", log_path.display()),
         Err(e) => {
             println!("Failed to open logger: {}", e);
-            return;
+            return Ok(());
         }
     }
 
-    let settings_path = format!("{}/Settings.json", settings_string);
-    if PathBuf::from_str(settings_path.as_str()).unwrap().exists() {
+    let settings_path = settings_dir.join("Settings.json");
+    if settings_path.exists() {
         let mut app_state = state.0.lock().unwrap();
-        let settings_raw_text = fs::read_to_string(settings_path).unwrap();
+        let settings_raw_text = fs::read_to_string(settings_path.as_path())
+            .map_err(|e| Error::Io(format!("Failed to read {}: {}", settings_path.display(), e)))?;
         app_state.settings = match serde_json::from_str(settings_raw_text.as_str()) {
             Ok(settings) => settings,
             Err(e) => {
                 error!("Failed to deserialize settings: {}", e);
+                set_aside_settings(settings_path.as_path());
                 Settings::default()
             }
         };
     }
+
+    Ok(())
+}
+
+/// Save the settings to the settings file, creating it and its folder if they do not exist
+/// # Arguments
+/// * `settings` - The settings to be saved
+fn save_settings(settings: &Settings) -> Result<(), Error> {
+    let settings_dir = settings_dir()?;
+    if !settings_dir.exists() {
+        match fs::create_dir(settings_dir.as_path()) {
+            Ok(_) => info!("Succesfully created settings folder."),
+            Err(e) => error!("Failed to create settings folder: {}", e),
+        }
+    }
+    let settings_path = settings_dir.join("Settings.json");
+    // Save or create a settings file
+    if settings_path.exists() {
+        let settings_file = File::options()
+            .write(true)
+            .truncate(true)
+            .open(settings_path.as_path())
+            .map_err(|e| Error::Io(format!("Failed to open {}: {}", settings_path.display(), e)))?;
+        serde_json::to_writer_pretty(settings_file, settings)?;
+        info!("Successfully saved settings.");
+    } else {
+        let mut settings_file = File::create(settings_path.as_path()).map_err(|e| {
+            Error::Io(format!(
+                "Failed to create {}: {}",
+                settings_path.display(),
+                e
+            ))
+        })?;
+        let settings_string = serde_json::to_string(settings)?;
+        settings_file
+            .write_all(settings_string.as_bytes())
+            .map_err(|e| {
+                Error::Io(format!(
+                    "Failed to write {}: {}",
+                    settings_path.display(),
+                    e
+                ))
+            })?;
+        info!("Successfully created new settings file.");
+    }
+
+    Ok(())
+}
+
+/// Move a settings file that could not be read out of the way, so that saving the default
+/// settings on exit does not overwrite it and it can still be recovered by hand
+/// # Arguments
+/// * `settings_path` - The path to the settings file
+fn set_aside_settings(settings_path: &Path) {
+    let mut unreadable_path = settings_path.with_file_name("Settings.unreadable.json");
+    let mut suffix = 1;
+    while unreadable_path.exists() {
+        unreadable_path =
+            settings_path.with_file_name(format!("Settings.unreadable.{}.json", suffix));
+        suffix += 1;
+    }
+    match fs::rename(settings_path, unreadable_path.as_path()) {
+        Ok(_) => warn!(
+            "Moved unreadable settings file to {}",
+            unreadable_path.display()
+        ),
+        Err(e) => error!(
+            "Failed to move aside unreadable settings file {}: {}",
+            settings_path.display(),
+            e
+        ),
+    }
 }
 
 /// Close Hollow Knight before starting the installer
@@ -1434,122 +3428,128 @@ fn exit_game() {
     }
 }
 
-/// Download a copy of the Modding API and replace local files with its contents if
-/// their hashes do not match; Also backs up the vanilla Assembly-CSharp.dll file.
+/// Download a copy of the Modding API, verify it against the hash on ApiLinks, and replace
+/// local files with its contents if their hashes do not match; Also backs up the vanilla
+/// Assembly-CSharp.dll file. Returns the version of the Modding API that was installed and
+/// where ApiLinks came from.
 /// # Arguments
 /// * `mods_path` - The path to the mods folder
-fn install_api(mods_path: String) {
+/// * `sources` - The sources to fetch ApiLinks and the Modding API from
+fn install_api(mods_path: String, sources: Sources) -> Result<(String, CacheStatus), Error> {
     let client = reqwest::blocking::Client::new();
-    let result = client
-        .get("https://raw.githubusercontent.com/hk-modding/modlinks/main/ApiLinks.xml")
-        .send()
-        .expect("Failed to get response for ApiLinks.");
-    let content = result.text().expect("Failed to get response string.");
-    let mut api_links = ApiLinks::new();
-    match quick_xml::de::from_str(content.as_str()) {
-        Ok(value) => {
-            info!("Successfully parsed API XML.");
-            api_links = value;
-            info!(
-                "API XML\n{}",
-                serde_json::to_string_pretty(&api_links).unwrap()
-            );
-        }
-        Err(e) => error!("Failed to parse API XML: {}", e),
+    let (api_links, cache_status) = fetch_cached(
+        &client,
+        &sources.api_links,
+        cache_dir()?.as_path(),
+        "ApiLinks.xml",
+        |content| {
+            quick_xml::de::from_str::<ApiLinks>(content)
+                .map_err(|e| Error::Parse(format!("Failed to parse API XML: {}", e)))
+        },
+    )?;
+    info!("Successfully parsed API XML.");
+    if cache_status.stale {
+        warn!("Using cached API XML from {}", cache_status.fetched_at);
     }
+    info!("API XML\n{}", serde_json::to_string_pretty(&api_links)?);
 
     let managed_path: PathBuf = [mods_path.as_str(), ".."].iter().collect();
-    let base_dir = BaseDirs::new().unwrap();
-    let settings_dir: PathBuf = [base_dir.data_dir().to_str().unwrap(), SETTINGS_FOLDER]
-        .iter()
-        .collect();
-    let temp_path: PathBuf = [
-        settings_dir
-            .into_os_string()
-            .into_string()
-            .unwrap()
-            .as_str(),
-        "..",
-        "Temp",
-    ]
-    .iter()
-    .collect();
-    let api_url: String;
+    let temp_path = settings_dir()?.join("..").join("Temp");
+    let api_source: SourceUrls;
+    let api_hash: String;
     match env::consts::OS {
         "linux" => {
-            api_url = String::from(
-                "https://github.com/hk-modding/api/releases/latest/download/ModdingApiLinux.zip",
-            )
+            api_source = sources.api_linux;
+            api_hash = api_links.manifest.links.linux.sha256.clone();
         }
-        "mac" => {
-            api_url = String::from(
-                "https://github.com/hk-modding/api/releases/latest/download/ModdingApiMac.zip",
-            )
+        "macos" => {
+            api_source = sources.api_mac;
+            api_hash = api_links.manifest.links.mac.sha256.clone();
         }
         "windows" => {
-            api_url = String::from(
-                "https://github.com/hk-modding/api/releases/latest/download/ModdingApiWin.zip",
-            )
+            api_source = sources.api_windows;
+            api_hash = api_links.manifest.links.windows.sha256.clone();
+        }
+        os => {
+            return Err(Error::InvalidState(format!(
+                "The Modding API is not available for {}.",
+                os
+            )))
         }
-        _ => panic!("OS not supported."),
     }
 
-    match reqwest::blocking::get(api_url) {
-        Ok(response) => {
-            let content = response.bytes().unwrap();
-            let reader = Cursor::new(content);
-            let unzipper = Unzipper::new(reader, temp_path.clone());
-            match unzipper.unzip() {
-                Ok(_) => info!("Successfully unzipped API to Temp folder."),
-                Err(e) => error!("Failed to unzip API to Temp folder: {}", e),
-            }
-        }
-        Err(e) => error!("Failed to get response: {}", e),
+    if api_hash.is_empty() {
+        return Err(Error::InvalidState(
+            "No SHA256 hash is available to verify the Modding API.".to_string(),
+        ));
     }
 
+    let mut attempt = 1;
+    let content = loop {
+        let content = fetch_bytes(&client, &api_source)
+            .map_err(|e| e.context("Failed to download the Modding API"))?;
+        let content_hash = digest_bytes(&content);
+        if content_hash.to_lowercase() == api_hash.to_lowercase() {
+            info!("Downloaded hash of the Modding API matches with that on ApiLinks.");
+            break content;
+        }
+
+        if attempt >= MAX_DOWNLOAD_ATTEMPTS {
+            return Err(Error::HashMismatch(format!(
+                "SHA256 mismatch for the Modding API after {} attempts: expected {}, got {}",
+                attempt, api_hash, content_hash
+            )));
+        }
+
+        warn!(
+            "Failed to verify SHA256 of the Modding API (attempt {} of {}), re-downloading...",
+            attempt, MAX_DOWNLOAD_ATTEMPTS
+        );
+        attempt += 1;
+    };
+
+    let reader = Cursor::new(content);
+    let unzipper = Unzipper::new(reader, temp_path.clone());
+    unzipper
+        .unzip()
+        .map_err(|e| Error::Io(format!("Failed to unzip API to Temp folder: {}", e)))?;
+    info!("Successfully unzipped API to Temp folder.");
+
+    let digest = |path: &Path| {
+        digest_file(path).map_err(|e| Error::Io(format!("Failed to hash {:?}: {}", path, e)))
+    };
     for file in api_links.manifest.files.files {
-        let temp_file: PathBuf = [temp_path.to_str().unwrap(), file.as_str()]
-            .iter()
-            .collect();
-        let local_file: PathBuf = [managed_path.to_str().unwrap(), file.as_str()]
-            .iter()
-            .collect();
+        let temp_file = temp_path.join(file.as_str());
+        let local_file = managed_path.join(file.as_str());
         if !local_file.exists() {
-            match fs::rename(temp_file, local_file) {
-                Ok(_) => info!(
-                    "Successfully moved temp file for {:?} to Managed folder.",
-                    file
-                ),
-                Err(e) => error!(
+            fs::rename(temp_file, local_file).map_err(|e| {
+                Error::Io(format!(
                     "Failed to move temp file for {:?} to Managed folder: {}",
                     file, e
-                ),
-            }
-        } else if digest_file(temp_file.clone()).unwrap()
-            != digest_file(local_file.clone()).unwrap()
-        {
+                ))
+            })?;
+            info!(
+                "Successfully moved temp file for {:?} to Managed folder.",
+                file
+            );
+        } else if digest(temp_file.as_path())? != digest(local_file.as_path())? {
             if file == "Assembly-CSharp.dll" {
-                let vanilla_backup: PathBuf = [
-                    managed_path.to_str().unwrap(),
-                    "Assembly-CSharp.dll.vanilla",
-                ]
-                .iter()
-                .collect();
-                match fs::rename(local_file.clone(), vanilla_backup) {
-                    Ok(_) => info!("Successfully backed up vanilla Assembly-CSharp."),
-                    Err(e) => error!("Failed to backup vanilla Assembly-Csharp: {}", e),
-                }
+                let vanilla_backup = managed_path.join("Assembly-CSharp.dll.vanilla");
+                fs::rename(local_file.clone(), vanilla_backup).map_err(|e| {
+                    Error::Io(format!("Failed to backup vanilla Assembly-CSharp: {}", e))
+                })?;
+                info!("Successfully backed up vanilla Assembly-CSharp.");
             }
-            match fs::rename(temp_file, local_file) {
-                Ok(_) => info!(
-                    "Successfully replaced old local file for {:?} with new API file.",
-                    file
-                ),
-                Err(e) => error!(
+            fs::rename(temp_file, local_file).map_err(|e| {
+                Error::Io(format!(
                     "Failed to replace old local file for {:?} with new API file: {}",
                     file, e
-                ),
-            }
+                ))
+            })?;
+            info!(
+                "Successfully replaced old local file for {:?} with new API file.",
+                file
+            );
         }
     }
 
@@ -1557,40 +3557,213 @@ fn install_api(mods_path: String) {
         Ok(_) => info!("Successfully deleted Temp folder."),
         Err(e) => error!("Failed to delete Temp folder: {}", e),
     }
+
+    info!(
+        "Installed version {} of the Modding API.",
+        api_links.manifest.version
+    );
+    Ok((api_links.manifest.version, cache_status))
 }
 
 /// Manually select the path of the game's executable
 /// # Arguments
 /// * `app` - The mutex guarding the application state
-fn select_game_path(mut app: MutexGuard<App>) {
+fn select_game_path(mut app: MutexGuard<App>) -> Result<(), Error> {
     warn!("Selecting game path manually.");
     let selected_path = FileDialog::new()
         .set_location("~")
         .show_open_single_dir()
-        .unwrap();
+        .map_err(|e| Error::Io(format!("Failed to open file dialog: {}", e)))?;
     let selected_path = match selected_path {
         Some(path) => path,
         None => {
             error!("Selected path is not valid.");
-            return;
+            return Ok(());
         }
     };
 
     match SUFFIXES.into_iter().find(|suffix| {
-        let path_buf: PathBuf = [selected_path.clone(), PathBuf::from_str(suffix).unwrap()]
-            .iter()
-            .collect();
-        info!(
-            "Checking selected path: {}",
-            path_buf.clone().to_str().unwrap()
-        );
+        let path_buf = selected_path.join(suffix);
+        info!("Checking selected path: {}", path_buf.display());
         path_buf.exists()
     }) {
         Some(suffix) => {
-            (*app).settings.mods_path =
-                format!("{}/{}/Mods", selected_path.to_str().unwrap(), suffix);
+            app.settings.mods_path =
+                format!("{}/{}/Mods", path_str(selected_path.as_path())?, suffix);
         }
         None => error!("No managed path found."),
     }
     info!("Selected mod path as: {}", app.settings.mods_path);
+    Ok(())
+}
+
+/// Get a path as a string, failing if it is not valid Unicode
+/// # Arguments
+/// * `path` - The path
+fn path_str(path: &Path) -> Result<&str, Error> {
+    path.to_str().ok_or_else(|| {
+        Error::InvalidState(format!("The path {} is not valid Unicode.", path.display()))
+    })
+}
+
+/// Get the path to the folder that Butterfly stores its settings and logs in
+fn settings_dir() -> Result<PathBuf, Error> {
+    Ok(base_dirs()?.data_dir().join(SETTINGS_FOLDER))
+}
+
+/// Get the path to the folder that the last successfully fetched ModLinks and ApiLinks are cached in
+fn cache_dir() -> Result<PathBuf, Error> {
+    Ok(settings_dir()?.join("Cache"))
+}
+
+/// Get the standard folders of the user's system, failing if the home folder cannot be found
+fn base_dirs() -> Result<BaseDirs, Error> {
+    BaseDirs::new().ok_or_else(|| {
+        Error::InvalidState("The home folder of the current user could not be found.".to_string())
+    })
+}
+
+/// Get the path to the game's Mods folder, failing if the game has not been found
+/// # Arguments
+/// * `settings` - The application's settings
+fn game_mods_path(settings: &Settings) -> Result<String, Error> {
+    if settings.mods_path.is_empty() {
+        return Err(Error::MissingGamePath(
+            "The path to Hollow Knight has not been found or selected.".to_string(),
+        ));
+    }
+
+    Ok(settings.mods_path.clone())
+}
+
+/// Get the path chosen in a file dialog, treating a dialog that was closed without choosing a
+/// path as the user cancelling
+/// # Arguments
+/// * `path` - The result of showing the file dialog
+/// * `purpose` - What the path is chosen for, completing "No file was selected to ..."
+fn selected_path(
+    path: native_dialog::Result<Option<PathBuf>>,
+    purpose: &str,
+) -> Result<PathBuf, Error> {
+    match path {
+        Ok(Some(path)) => Ok(path),
+        Ok(None) => Err(Error::Cancelled(format!(
+            "No file was selected to {}.",
+            purpose
+        ))),
+        Err(e) => Err(Error::Io(format!("Failed to open file dialog: {}", e))),
+    }
+}
+
+/// Get the names of the mod folders in a folder, excluding the Disabled folder
+/// # Arguments
+/// * `path` - The folder to look in
+fn mod_folders(path: &Path) -> Vec<String> {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name != "Disabled")
+        .collect()
+}
+
+/// Get the path to the folder the game keeps its saves and mod global settings in
+fn saves_dir() -> Result<PathBuf, Error> {
+    os_saves_dir(env::consts::OS, &base_dirs()?)
+}
+
+/// Get the path to the folder the game keeps its saves and mod global settings in on an OS
+/// # Arguments
+/// * `os` - The OS, as named by `env::consts::OS`
+/// * `base_dir` - The standard folders of the user's system
+fn os_saves_dir(os: &str, base_dir: &BaseDirs) -> Result<PathBuf, Error> {
+    match os {
+        "linux" => Ok(base_dir
+            .config_dir()
+            .join("unity3d/Team Cherry/Hollow Knight")),
+        "macos" => Ok(base_dir.data_dir().join("unity.Team Cherry.Hollow Knight")),
+        "windows" => Ok(base_dir
+            .data_dir()
+            .join("../LocalLow/Team Cherry/Hollow Knight")),
+        _ => Err(Error::InvalidState(format!(
+            "Saves are not supported on {}.",
+            os
+        ))),
+    }
+}
+
+/// Get the path to the folder that data owned by a profile is stored in
+/// # Arguments
+/// * `profile_name` - The name of the profile
+fn profile_data_dir(profile_name: &str) -> Result<PathBuf, Error> {
+    Ok(settings_dir()?
+        .join(PROFILE_DATA_FOLDER)
+        .join(profile_folder_name(profile_name)))
+}
+
+/// Get the path to the folder that data shared by profiles without their own is stored in
+fn shared_data_dir() -> Result<PathBuf, Error> {
+    Ok(settings_dir()?.join(SHARED_DATA_FOLDER))
+}
+
+/// Get the folder holding the set of global settings that a profile uses
+/// # Arguments
+/// * `profiles` - The profiles in settings
+/// * `profile_name` - The name of the profile, which may be empty if no profile is active
+fn global_settings_set(profiles: &[Profile], profile_name: &str) -> Result<PathBuf, Error> {
+    if profiles
+        .iter()
+        .any(|p| p.name == profile_name && p.global_settings)
+    {
+        Ok(profile_data_dir(profile_name)?.join(GLOBAL_SETTINGS_FOLDER))
+    } else {
+        Ok(shared_data_dir()?.join(GLOBAL_SETTINGS_FOLDER))
+    }
+}
+
+/// Get the folder holding the set of saves that a profile uses
+/// # Arguments
+/// * `profiles` - The profiles in settings
+/// * `profile_name` - The name of the profile, which may be empty if no profile is active
+fn saves_set(profiles: &[Profile], profile_name: &str) -> Result<PathBuf, Error> {
+    if profiles.iter().any(|p| p.name == profile_name && p.saves) {
+        Ok(profile_data_dir(profile_name)?.join(SAVES_FOLDER))
+    } else {
+        Ok(shared_data_dir()?.join(SAVES_FOLDER))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_dir_for_each_os() {
+        let base_dir = BaseDirs::new().unwrap();
+        assert_eq!(
+            os_saves_dir("linux", &base_dir).unwrap(),
+            base_dir
+                .config_dir()
+                .join("unity3d/Team Cherry/Hollow Knight")
+        );
+        assert_eq!(
+            os_saves_dir("macos", &base_dir).unwrap(),
+            base_dir.data_dir().join("unity.Team Cherry.Hollow Knight")
+        );
+        assert_eq!(
+            os_saves_dir("windows", &base_dir).unwrap(),
+            base_dir
+                .data_dir()
+                .join("../LocalLow/Team Cherry/Hollow Knight")
+        );
+        assert!(matches!(
+            os_saves_dir("freebsd", &base_dir),
+            Err(Error::InvalidState(_))
+        ));
+    }
 }
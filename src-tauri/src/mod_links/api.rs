@@ -38,32 +38,4 @@ pub struct ApiManifest {
 pub struct ApiLinks {
     #[serde(rename = "Manifest")]
     pub manifest: ApiManifest
-}
-
-impl ApiLinks {
-    /// Create a new instance of an api links object
-    pub fn new() -> ApiLinks {
-        ApiLinks {
-            manifest: ApiManifest {
-                version: "".to_string(),
-                links: ApiPlatformLinks {
-                    linux: ApiLink {
-                        sha256: "".to_string(),
-                        link: "".to_string(),
-                    },
-                    mac: ApiLink {
-                        sha256: "".to_string(),
-                        link: "".to_string(),
-                    },
-                    windows: ApiLink {
-                        sha256: "".to_string(),
-                        link: "".to_string(),
-                    }
-                },
-                files: ApiFiles {
-                    files: vec![],
-                }
-            },
-        }
-    }
 }
\ No newline at end of file
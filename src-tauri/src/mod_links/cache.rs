@@ -0,0 +1,251 @@
+use crate::app::clock::now_millis;
+use crate::app::sources::{local_path, SourceUrls};
+use crate::error::Error;
+use log::{error, info, warn};
+use reqwest::blocking::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha256::digest;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Metadata about a document that was last fetched successfully, stored next to it in the cache
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CacheMetadata {
+    /// The URL the document was fetched from; conditional requests are only sent to this URL
+    #[serde(rename = "URL", default)]
+    pub url: String,
+    #[serde(rename = "ETag", default)]
+    pub etag: Option<String>,
+    #[serde(rename = "Last Modified", default)]
+    pub last_modified: Option<String>,
+    /// The time the document was last confirmed to be up to date, in milliseconds since the Unix epoch
+    #[serde(rename = "Fetched At", default)]
+    pub fetched_at: u64,
+}
+
+/// Where a fetched document came from
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct CacheStatus {
+    /// Whether the document could not be fetched and was loaded from the cache instead
+    #[serde(rename = "Stale")]
+    pub stale: bool,
+    /// The time the document was last confirmed to be up to date, in milliseconds since the Unix epoch
+    #[serde(rename = "Fetched At")]
+    pub fetched_at: u64,
+}
+
+/// Get the name of the file a custom feed is cached as. It is named after a hash of the feed's
+/// primary URL, so that no two feeds share a file whatever their names.
+/// # Arguments
+/// * `source` - The URLs the feed is fetched from
+pub fn feed_file_name(source: &SourceUrls) -> String {
+    format!("Feed-{}.xml", digest(source.primary.as_str()))
+}
+
+/// Fetch a document from the first of its sources that responds, using a conditional request
+/// if it has been cached from that source before. If the document cannot be fetched or parsed
+/// from any source, the last successfully fetched copy is used instead.
+/// # Arguments
+/// * `client` - The client to send requests with
+/// * `source` - The URLs to fetch the document from, in order
+/// * `cache_dir` - The folder that cached documents are stored in
+/// * `file_name` - The name of the document's file in the cache
+/// * `parse` - Parses the document's contents, rejecting documents that should not be cached
+pub fn fetch_cached<T>(
+    client: &Client,
+    source: &SourceUrls,
+    cache_dir: &Path,
+    file_name: &str,
+    parse: impl Fn(&str) -> Result<T, Error>,
+) -> Result<(T, CacheStatus), Error> {
+    let content_path: PathBuf = [cache_dir, Path::new(file_name)].iter().collect();
+    let metadata_path = content_path.with_file_name(format!("{}.json", file_name));
+    let metadata: Option<CacheMetadata> = if content_path.exists() {
+        fs::read_to_string(metadata_path.as_path())
+            .ok()
+            .and_then(|text| serde_json::from_str(text.as_str()).ok())
+    } else {
+        None
+    };
+
+    let mut errors = vec![];
+    for url in source.urls() {
+        let cached = metadata.as_ref().filter(|m| m.url == url);
+        match fetch_from(client, url, file_name, cached) {
+            Ok(Fetched::NotModified) => {
+                info!("{} has not changed since it was last fetched.", file_name);
+                match fs::read_to_string(content_path.as_path()) {
+                    Ok(content) => match parse(content.as_str()) {
+                        Ok(value) => {
+                            let mut metadata = cached.unwrap().clone();
+                            metadata.fetched_at = now_millis();
+                            // The document was fetched, so failing to cache it only costs the next fetch
+                            if let Err(e) = write_metadata(metadata_path.as_path(), &metadata) {
+                                error!("{}", e);
+                            }
+                            let status = CacheStatus {
+                                stale: false,
+                                fetched_at: metadata.fetched_at,
+                            };
+                            return Ok((value, status));
+                        }
+                        Err(e) => errors.push(e),
+                    },
+                    Err(e) => errors.push(Error::Io(format!(
+                        "Failed to read cached {}: {}",
+                        file_name, e
+                    ))),
+                }
+            }
+            Ok(Fetched::Content(content, metadata)) => match parse(content.as_str()) {
+                Ok(value) => {
+                    store(cache_dir, content_path.as_path(), content.as_str());
+                    // The document was fetched, so failing to cache it only costs the next fetch
+                    if let Err(e) = write_metadata(metadata_path.as_path(), &metadata) {
+                        error!("{}", e);
+                    }
+                    let status = CacheStatus {
+                        stale: false,
+                        fetched_at: metadata.fetched_at,
+                    };
+                    return Ok((value, status));
+                }
+                Err(e) => errors.push(e),
+            },
+            Err(e) => errors.push(e),
+        }
+        warn!("{}", errors.last().unwrap());
+    }
+
+    let fetch_error = if errors.is_empty() {
+        format!("No URLs are configured for {}", file_name)
+    } else {
+        errors
+            .iter()
+            .map(Error::message)
+            .collect::<Vec<&str>>()
+            .join("; ")
+    };
+    warn!("Falling back to the cached copy of {}.", file_name);
+    let content = fs::read_to_string(content_path.as_path())
+        .map_err(|_| Error::Network(format!("{} and no cached copy is available", fetch_error)))?;
+    let status = CacheStatus {
+        stale: true,
+        fetched_at: metadata.map(|m| m.fetched_at).unwrap_or_default(),
+    };
+    Ok((parse(content.as_str())?, status))
+}
+
+/// The result of fetching a document from a single URL
+enum Fetched {
+    /// The cached copy of the document is still up to date
+    NotModified,
+    /// The document's contents along with the metadata to cache them with
+    Content(String, CacheMetadata),
+}
+
+/// Fetch a document from a single URL, which may be a file:// URL
+/// # Arguments
+/// * `client` - The client to send the request with
+/// * `url` - The URL of the document
+/// * `file_name` - The name of the document, used in error messages
+/// * `cached` - The metadata of the copy cached from this URL, if any
+fn fetch_from(
+    client: &Client,
+    url: &str,
+    file_name: &str,
+    cached: Option<&CacheMetadata>,
+) -> Result<Fetched, Error> {
+    if let Some(path) = local_path(url) {
+        let content = fs::read_to_string(path.as_path())
+            .map_err(|e| Error::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+        let metadata = CacheMetadata {
+            url: url.to_string(),
+            fetched_at: now_millis(),
+            ..Default::default()
+        };
+        return Ok(Fetched::Content(content, metadata));
+    }
+
+    let mut request = client.get(url);
+    if let Some(metadata) = cached {
+        if let Some(etag) = &metadata.etag {
+            request = request.header(IF_NONE_MATCH, etag.as_str());
+        }
+        if let Some(last_modified) = &metadata.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified.as_str());
+        }
+    }
+
+    let response = request.send().map_err(|e| {
+        Error::Network(format!("Failed to fetch {} from {}: {}", file_name, url, e))
+    })?;
+    if response.status() == StatusCode::NOT_MODIFIED && cached.is_some() {
+        return Ok(Fetched::NotModified);
+    }
+    if !response.status().is_success() {
+        return Err(Error::Network(format!(
+            "Fetching {} from {} returned status {}",
+            file_name,
+            url,
+            response.status()
+        )));
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+    let metadata = CacheMetadata {
+        url: url.to_string(),
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+        fetched_at: now_millis(),
+    };
+    let content = response.text().map_err(|e| {
+        Error::Network(format!(
+            "Failed to get content of {} from {}: {}",
+            file_name, url, e
+        ))
+    })?;
+    Ok(Fetched::Content(content, metadata))
+}
+
+/// Write a successfully fetched document to the cache
+/// # Arguments
+/// * `cache_dir` - The folder that cached documents are stored in
+/// * `content_path` - The path to write the document to
+/// * `content` - The contents of the document
+fn store(cache_dir: &Path, content_path: &Path, content: &str) {
+    if !cache_dir.exists() {
+        if let Err(e) = fs::create_dir_all(cache_dir) {
+            error!("Failed to create cache folder: {}", e);
+            return;
+        }
+    }
+
+    match fs::write(content_path, content) {
+        Ok(_) => info!("Successfully cached {}", content_path.display()),
+        Err(e) => error!("Failed to cache {}: {}", content_path.display(), e),
+    }
+}
+
+/// Write the metadata of a cached document
+/// # Arguments
+/// * `metadata_path` - The path to write the metadata to
+/// * `metadata` - The metadata of the cached document
+fn write_metadata(metadata_path: &Path, metadata: &CacheMetadata) -> Result<(), Error> {
+    let text = serde_json::to_string_pretty(metadata)?;
+    fs::write(metadata_path, text).map_err(|e| {
+        Error::Io(format!(
+            "Failed to write cache metadata {}: {}",
+            metadata_path.display(),
+            e
+        ))
+    })
+}
@@ -0,0 +1,147 @@
+use crate::error::Error;
+use crate::mod_links::remote::RemoteModManifest;
+use std::collections::HashMap;
+
+/// The visitation state of a mod while walking the dependency graph
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+/// Resolve the full transitive dependency closure of a set of mods.
+/// The returned names are ordered so that every mod comes after all of its dependencies.
+/// # Arguments
+/// * `mod_names` - The names of the mods to resolve dependencies for
+/// * `manifests` - The manifests fetched from ModLinks
+pub fn resolve_dependencies(
+    mod_names: &[String],
+    manifests: &[RemoteModManifest],
+) -> Result<Vec<String>, Error> {
+    let manifests: HashMap<&str, &RemoteModManifest> =
+        manifests.iter().map(|m| (m.name.as_str(), m)).collect();
+    let mut visits: HashMap<String, Visit> = HashMap::new();
+    let mut missing: Vec<String> = vec![];
+    let mut order: Vec<String> = vec![];
+    for mod_name in mod_names {
+        let mut path = vec![];
+        visit(
+            mod_name,
+            &manifests,
+            &mut visits,
+            &mut path,
+            &mut missing,
+            &mut order,
+        )?;
+    }
+
+    if !missing.is_empty() {
+        return Err(Error::InvalidState(format!(
+            "The following mods could not be found on ModLinks: {}",
+            missing.join(", ")
+        )));
+    }
+
+    Ok(order)
+}
+
+/// Depth-first walk of a mod's dependencies
+/// # Arguments
+/// * `mod_name` - The name of the mod being visited
+/// * `manifests` - The manifests fetched from ModLinks, keyed by name
+/// * `visits` - The visitation state of every mod seen so far
+/// * `path` - The chain of mods leading to the current one, used to report cycles
+/// * `missing` - The names of mods that are not on ModLinks
+/// * `order` - The resolved install order
+fn visit(
+    mod_name: &str,
+    manifests: &HashMap<&str, &RemoteModManifest>,
+    visits: &mut HashMap<String, Visit>,
+    path: &mut Vec<String>,
+    missing: &mut Vec<String>,
+    order: &mut Vec<String>,
+) -> Result<(), Error> {
+    match visits.get(mod_name) {
+        Some(Visit::Done) => return Ok(()),
+        Some(Visit::InProgress) => {
+            path.push(mod_name.to_string());
+            let start = path.iter().position(|name| name == mod_name).unwrap_or(0);
+            return Err(Error::InvalidState(format!(
+                "Dependency cycle detected: {}",
+                path[start..].join(" -> ")
+            )));
+        }
+        None => (),
+    }
+
+    let manifest = match manifests.get(mod_name) {
+        Some(manifest) => manifest,
+        None => {
+            if !missing.iter().any(|name| name == mod_name) {
+                missing.push(mod_name.to_string());
+            }
+            return Ok(());
+        }
+    };
+
+    visits.insert(mod_name.to_string(), Visit::InProgress);
+    path.push(mod_name.to_string());
+    for dependency in &manifest.dependencies.dependencies {
+        visit(dependency, manifests, visits, path, missing, order)?;
+    }
+    path.pop();
+    visits.insert(mod_name.to_string(), Visit::Done);
+    order.push(mod_name.to_string());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str, dependencies: &[&str]) -> RemoteModManifest {
+        serde_json::from_value(serde_json::json!({
+            "Name": name,
+            "Link": { "SHA256": "", "$value": "" },
+            "Dependencies": { "Dependency": dependencies },
+            "Repository": "",
+        }))
+        .unwrap()
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let manifests = vec![manifest("a", &["b"]), manifest("b", &["a"])];
+        let err = resolve_dependencies(&names(&["a"]), &manifests).unwrap_err();
+        assert_eq!(err.message(), "Dependency cycle detected: a -> b -> a");
+    }
+
+    #[test]
+    fn reports_missing_dependencies() {
+        let manifests = vec![manifest("a", &["gone"])];
+        let err = resolve_dependencies(&names(&["a"]), &manifests).unwrap_err();
+        assert!(err.message().contains("gone"));
+    }
+
+    #[test]
+    fn orders_dependencies_before_dependents() {
+        let manifests = vec![
+            manifest("top", &["left", "right"]),
+            manifest("left", &["base"]),
+            manifest("right", &["base"]),
+            manifest("base", &[]),
+        ];
+        let order = resolve_dependencies(&names(&["top"]), &manifests).unwrap();
+        let position = |name: &str| order.iter().position(|n| n == name).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(position("base") < position("left"));
+        assert!(position("base") < position("right"));
+        assert!(position("left") < position("top"));
+        assert!(position("right") < position("top"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::mod_links::version::ModVersion;
 use serde::{self, Deserialize, Serialize};
 
 /// The object listing all the dependencies of a mod
@@ -32,7 +33,7 @@ pub struct LocalModManifest {
     #[serde(rename = "Description", default)]
     pub description: String,
     #[serde(rename = "Version", default)]
-    pub version: String,
+    pub version: ModVersion,
     #[serde(rename = "Link")]
     pub link: ModLink,
     #[serde(rename = "Dependencies")]
@@ -42,6 +43,9 @@ pub struct LocalModManifest {
     #[serde(rename = "Tags")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<ModTags>,
+    /// The name of the feed the mod was listed on
+    #[serde(rename = "Source", default)]
+    pub source: String,
     #[serde(rename = "Enabled")]
     pub enabled: bool,
     #[serde(rename = "Installed")]
@@ -50,16 +54,8 @@ pub struct LocalModManifest {
 
 /// The main mod links object loaded from settings file;
 /// local to settings file
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct LocalModLinks {
     #[serde(rename = "Manifest", default)]
     pub manifests: Vec<LocalModManifest>,
-}
-
-impl Default for LocalModLinks {
-    fn default() -> Self {
-        LocalModLinks {
-            manifests: vec![],
-        }
-    }
 }
\ No newline at end of file
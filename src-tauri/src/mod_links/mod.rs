@@ -1,3 +1,8 @@
 pub mod api;
+pub mod cache;
+pub mod dependencies;
 pub mod local;
-pub mod remote;
\ No newline at end of file
+pub mod remote;
+pub mod staged_download;
+pub mod staged_install;
+pub mod version;
\ No newline at end of file
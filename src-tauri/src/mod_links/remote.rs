@@ -1,4 +1,5 @@
-use crate::mod_links::local::{ModDependencies, ModLink, ModTags};
+use crate::mod_links::local::{LocalModLinks, ModDependencies, ModLink, ModTags};
+use crate::mod_links::version::{ModVersion, VersionChange};
 use serde::{self, Deserialize, Serialize};
 
 /// The manifest object containing data about an individual mod;
@@ -10,7 +11,7 @@ pub struct RemoteModManifest {
     #[serde(rename = "Description", default)]
     pub description: String,
     #[serde(rename = "Version", default)]
-    pub version: String,
+    pub version: ModVersion,
     #[serde(rename = "Link")]
     pub link: ModLink,
     #[serde(rename = "Dependencies")]
@@ -20,12 +21,26 @@ pub struct RemoteModManifest {
     #[serde(rename = "Tags")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<ModTags>,
+    /// The name of the feed the mod was listed on
+    #[serde(skip_deserializing, rename = "Source")]
+    pub source: String,
     #[serde(skip_deserializing, rename = "Enabled")]
     pub enabled: bool,
     #[serde(skip_deserializing, rename = "Installed")]
     pub installed: bool,
 }
 
+/// The differences between the mod links saved in settings and those fetched from GitHub
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModLinksChanges {
+    /// Mods that are on ModLinks but were not in the saved mod links
+    pub new_mods: Vec<String>,
+    /// Mods that were in the saved mod links but are no longer on ModLinks
+    pub removed_mods: Vec<String>,
+    /// Installed mods whose version on ModLinks is newer than the version on disk
+    pub outdated_mods: Vec<String>,
+}
+
 /// The main mod links object fetched from GitHub
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct RemoteModLinks {
@@ -40,4 +55,64 @@ impl RemoteModLinks {
             manifests: vec![],
         }
     }
+    /// Merge the mods of another feed into these mod links. Mods already listed take
+    /// precedence, so the names of conflicting mods from the other feed are returned.
+    /// # Arguments
+    /// * `other` - The mod links of the feed to merge in
+    /// * `source` - The name of the feed to record as the source of each merged mod
+    pub fn merge(&mut self, other: RemoteModLinks, source: &str) -> Vec<String> {
+        let mut conflicts = vec![];
+        for mut manifest in other.manifests {
+            if self.manifests.iter().any(|m| m.name == manifest.name) {
+                conflicts.push(manifest.name);
+                continue;
+            }
+
+            manifest.source = source.to_string();
+            self.manifests.push(manifest);
+        }
+
+        conflicts
+    }
+
+    /// Compare these mod links against the mod links saved in settings. Installation status
+    /// is taken from these manifests, so they should already reflect what is on disk.
+    /// # Arguments
+    /// * `saved_mod_links` - The mod links saved in settings from the previous fetch
+    pub fn compare(&self, saved_mod_links: &LocalModLinks) -> ModLinksChanges {
+        let mut changes = ModLinksChanges::default();
+        // If saved mod links are empty, then this is a first run of the app.
+        if saved_mod_links.manifests.is_empty() {
+            return changes;
+        }
+
+        for manifest in &self.manifests {
+            match saved_mod_links
+                .manifests
+                .iter()
+                .find(|m| m.name == manifest.name)
+            {
+                Some(saved_manifest) => {
+                    if manifest.installed
+                        && saved_manifest.version.change_to(&manifest.version)
+                            == VersionChange::Upgrade
+                    {
+                        changes.outdated_mods.push(manifest.name.clone());
+                    }
+                }
+                None => changes.new_mods.push(manifest.name.clone()),
+            }
+        }
+
+        // Manually installed mods have no link and were never on ModLinks
+        for saved_manifest in &saved_mod_links.manifests {
+            if !saved_manifest.link.link.is_empty()
+                && !self.manifests.iter().any(|m| m.name == saved_manifest.name)
+            {
+                changes.removed_mods.push(saved_manifest.name.clone());
+            }
+        }
+
+        changes
+    }
 }
\ No newline at end of file
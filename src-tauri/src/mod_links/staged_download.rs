@@ -0,0 +1,225 @@
+use crate::app::downloads::DownloadTicket;
+use crate::app::progress::ProgressTracker;
+use crate::error::Error;
+use futures_util::StreamExt;
+use log::{info, warn};
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Metadata about a download that has not finished, stored next to its staging file so the
+/// download can be resumed
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PartialDownload {
+    #[serde(rename = "URL")]
+    pub url: String,
+    /// The SHA256 hash the finished download is expected to have
+    #[serde(rename = "SHA256")]
+    pub sha256: String,
+    #[serde(rename = "ETag", default)]
+    pub etag: Option<String>,
+    #[serde(rename = "Last Modified", default)]
+    pub last_modified: Option<String>,
+    #[serde(rename = "Total Size")]
+    pub total_size: u64,
+}
+
+impl PartialDownload {
+    /// Get the validator to send in an If-Range header, so the server only sends the rest of
+    /// the download if it has not changed. Weak ETags cannot be used for ranges.
+    fn validator(&self) -> Option<&str> {
+        match &self.etag {
+            Some(etag) if !etag.starts_with("W/") => Some(etag.as_str()),
+            _ => self.last_modified.as_deref(),
+        }
+    }
+}
+
+/// Get the paths to the staging file of a download and to its metadata. The download queue
+/// never runs two downloads with the same hash at once, so only one writes to them at a time.
+/// # Arguments
+/// * `staging_dir` - The folder that downloads are staged in
+/// * `sha256` - The expected SHA256 hash of the download, which names its files
+pub fn staging_paths(staging_dir: &Path, sha256: &str) -> (PathBuf, PathBuf) {
+    let name = sha256.to_lowercase();
+    (
+        staging_dir.join(format!("{}.part", name)),
+        staging_dir.join(format!("{}.json", name)),
+    )
+}
+
+/// Remove the staging file of a download and its metadata
+/// # Arguments
+/// * `staging_dir` - The folder that downloads are staged in
+/// * `sha256` - The expected SHA256 hash of the download
+pub fn discard_staged(staging_dir: &Path, sha256: &str) {
+    let (file_path, metadata_path) = staging_paths(staging_dir, sha256);
+    for path in [file_path, metadata_path] {
+        if path.exists() {
+            if let Err(e) = fs::remove_file(path.as_path()) {
+                warn!("Failed to remove {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Download a file into the staging folder, resuming an earlier partial download of it with a
+/// range request if the server supports it and the file has not changed since. Otherwise the
+/// file is downloaded from the start. Returns the path to the staged file, whose hash has not
+/// been checked yet.
+/// # Arguments
+/// * `client` - The client to send the request with
+/// * `tracker` - The tracker to report the progress of the download to
+/// * `ticket` - The download's place in the download queue, checked for pauses and cancellation
+/// * `url` - The URL of the file
+/// * `sha256` - The expected SHA256 hash of the file
+/// * `staging_dir` - The folder that downloads are staged in
+pub async fn download_staged(
+    client: &Client,
+    tracker: &mut ProgressTracker,
+    ticket: &DownloadTicket,
+    url: &str,
+    sha256: &str,
+    staging_dir: &Path,
+) -> Result<PathBuf, Error> {
+    fs::create_dir_all(staging_dir)
+        .map_err(|e| Error::Io(format!("Failed to create {}: {}", staging_dir.display(), e)))?;
+    let (file_path, metadata_path) = staging_paths(staging_dir, sha256);
+    let partial = read_partial(file_path.as_path(), metadata_path.as_path(), url, sha256);
+
+    let mut request = client.get(url);
+    if let Some((partial, bytes_done)) = &partial {
+        // The validator is checked by read_partial
+        let validator = partial.validator().unwrap_or_default();
+        info!(
+            "Resuming download of {} from {} of {} bytes",
+            url, bytes_done, partial.total_size
+        );
+        request = request
+            .header(RANGE, format!("bytes={}-", bytes_done))
+            .header(IF_RANGE, validator);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| Error::Network(format!("Failed to download {}: {}", url, e)))?;
+    let resumed = match &partial {
+        Some((partial, bytes_done)) if response.status() == StatusCode::PARTIAL_CONTENT => {
+            if range_start(&response) != Some(*bytes_done) {
+                return Err(Error::Network(format!(
+                    "{} returned a range that does not continue the download",
+                    url
+                )));
+            }
+            Some((partial.clone(), *bytes_done))
+        }
+        Some(_) => {
+            info!(
+                "{} cannot be resumed or has changed, downloading it again.",
+                url
+            );
+            None
+        }
+        None => None,
+    };
+    if !response.status().is_success() {
+        return Err(Error::Network(format!(
+            "Downloading {} returned status {}",
+            url,
+            response.status()
+        )));
+    }
+
+    let (metadata, bytes_done) = match resumed {
+        Some(resumed) => resumed,
+        None => {
+            let header = |name| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.to_string())
+            };
+            let total_size = response.content_length().ok_or_else(|| {
+                Error::Network(format!("Failed to get content length from {}", url))
+            })?;
+            let metadata = PartialDownload {
+                url: url.to_string(),
+                sha256: sha256.to_string(),
+                etag: header(ETAG),
+                last_modified: header(LAST_MODIFIED),
+                total_size,
+            };
+            let text = serde_json::to_string_pretty(&metadata)?;
+            fs::write(metadata_path.as_path(), text).map_err(|e| {
+                Error::Io(format!(
+                    "Failed to write {}: {}",
+                    metadata_path.display(),
+                    e
+                ))
+            })?;
+            (metadata, 0)
+        }
+    };
+
+    let mut file = if bytes_done > 0 {
+        File::options().append(true).open(file_path.as_path())
+    } else {
+        File::create(file_path.as_path())
+    }
+    .map_err(|e| Error::Io(format!("Failed to open {}: {}", file_path.display(), e)))?;
+    tracker.start_download(bytes_done, metadata.total_size);
+    let mut stream = response.bytes_stream();
+    while let Some(item) = stream.next().await {
+        ticket.checkpoint()?;
+        let chunk =
+            item.map_err(|e| Error::Network(format!("Failed to download {}: {}", url, e)))?;
+        file.write_all(&chunk)
+            .map_err(|e| Error::Io(format!("Failed to write {}: {}", file_path.display(), e)))?;
+        tracker.advance(chunk.len() as u64);
+    }
+
+    Ok(file_path)
+}
+
+/// Read the metadata of an earlier partial download of a file, if it can be resumed. Returns
+/// the metadata along with the number of bytes already downloaded.
+/// # Arguments
+/// * `file_path` - The path to the staging file
+/// * `metadata_path` - The path to the staging file's metadata
+/// * `url` - The URL the file is being downloaded from
+/// * `sha256` - The expected SHA256 hash of the file
+fn read_partial(
+    file_path: &Path,
+    metadata_path: &Path,
+    url: &str,
+    sha256: &str,
+) -> Option<(PartialDownload, u64)> {
+    let metadata: PartialDownload = fs::read_to_string(metadata_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(text.as_str()).ok())?;
+    let bytes_done = fs::metadata(file_path).ok()?.len();
+    let resumable = metadata.url == url
+        && metadata.sha256.eq_ignore_ascii_case(sha256)
+        && metadata.validator().is_some()
+        && bytes_done > 0
+        && bytes_done < metadata.total_size;
+    if resumable {
+        Some((metadata, bytes_done))
+    } else {
+        None
+    }
+}
+
+/// Get the first byte of the range a partial response contains, from its Content-Range header
+/// # Arguments
+/// * `response` - The partial response
+fn range_start(response: &Response) -> Option<u64> {
+    let content_range = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let range = content_range.strip_prefix("bytes ")?;
+    range.split('-').next()?.trim().parse().ok()
+}
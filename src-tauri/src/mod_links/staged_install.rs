@@ -0,0 +1,103 @@
+use crate::error::Error;
+use log::{error, info, warn};
+use std::fs;
+use std::path::Path;
+
+/// Check that the files of a mod extracted into a staging folder look like a mod, which is
+/// that there is at least one DLL among them
+/// # Arguments
+/// * `staged_dir` - The staging folder the mod was extracted into
+/// * `mod_name` - The name of the mod, used in the error message
+pub fn validate_staged(staged_dir: &Path, mod_name: &str) -> Result<(), Error> {
+    if contains_dll(staged_dir) {
+        Ok(())
+    } else {
+        Err(Error::InvalidState(format!(
+            "The download of mod {:?} does not contain a DLL.",
+            mod_name
+        )))
+    }
+}
+
+/// Move a mod's staged folder into place, replacing the installed version of the mod if there
+/// is one. The installed version is moved aside rather than removed until the staged folder has
+/// been moved, and is moved back if that fails. Both folders must be on the same drive as the
+/// mod's folder, so that every move is a rename.
+/// # Arguments
+/// * `staged_dir` - The folder the mod was staged in
+/// * `mod_path` - The mod's folder in the Mods or Disabled folder
+/// * `backup_dir` - The folder the installed version is moved aside to
+pub fn swap_into_place(staged_dir: &Path, mod_path: &Path, backup_dir: &Path) -> Result<(), Error> {
+    if let Some(parent) = mod_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| Error::Io(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+    if backup_dir.exists() {
+        fs::remove_dir_all(backup_dir)
+            .map_err(|e| Error::Io(format!("Failed to remove {}: {}", backup_dir.display(), e)))?;
+    }
+
+    let replacing = mod_path.exists();
+    if replacing {
+        fs::rename(mod_path, backup_dir).map_err(|e| {
+            Error::Io(format!(
+                "Failed to move aside installed version in {}: {}",
+                mod_path.display(),
+                e
+            ))
+        })?;
+    }
+
+    if let Err(e) = fs::rename(staged_dir, mod_path) {
+        if replacing {
+            match fs::rename(backup_dir, mod_path) {
+                Ok(_) => info!("Restored installed version in {}", mod_path.display()),
+                Err(e) => error!(
+                    "Failed to restore installed version in {} from {}: {}",
+                    mod_path.display(),
+                    backup_dir.display(),
+                    e
+                ),
+            }
+        }
+        return Err(Error::Io(format!(
+            "Failed to move staged install into {}: {}",
+            mod_path.display(),
+            e
+        )));
+    }
+
+    if replacing {
+        if let Err(e) = fs::remove_dir_all(backup_dir) {
+            warn!(
+                "Failed to remove previous version {}: {}",
+                backup_dir.display(),
+                e
+            );
+        }
+    }
+    info!("Moved staged install into {}", mod_path.display());
+    Ok(())
+}
+
+/// Whether a folder or any folder inside it contains a DLL, which is what makes a folder a mod,
+/// both for checking staged installs and for finding manually installed mods
+/// # Arguments
+/// * `dir` - The folder to search
+pub fn contains_dll(dir: &Path) -> bool {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+
+    entries.filter_map(|entry| entry.ok()).any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            contains_dll(path.as_path())
+        } else {
+            path.extension()
+                .map(|ext| ext.eq_ignore_ascii_case("dll"))
+                .unwrap_or(false)
+        }
+    })
+}
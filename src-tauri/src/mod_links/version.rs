@@ -0,0 +1,195 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// The number of components in a .NET version, i.e. major.minor.build.revision
+const COMPONENT_COUNT: usize = 4;
+
+/// A mod version as listed on ModLinks, e.g. 1.5.78.11833;
+/// Versions that cannot be parsed, such as those of manually installed mods, are unknown
+#[derive(Clone, Debug)]
+pub struct ModVersion {
+    /// The parsed components of the version, with missing components set to zero
+    components: Option<[u64; COMPONENT_COUNT]>,
+    /// The version as originally written
+    raw: String,
+}
+
+/// How a mod's version changes when replacing one version with another
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VersionChange {
+    Upgrade,
+    Downgrade,
+    Identical,
+    /// At least one of the versions is unknown, so they cannot be compared
+    Unknown,
+}
+
+impl ModVersion {
+    /// Create a version for a mod whose version is not known
+    pub fn unknown() -> ModVersion {
+        ModVersion {
+            components: None,
+            raw: String::from("Unknown"),
+        }
+    }
+
+    /// Whether the version could be parsed into its components
+    pub fn is_known(&self) -> bool {
+        self.components.is_some()
+    }
+
+    /// Determine how the version changes when going from this version to another. Two unknown
+    /// versions are not identical, since nothing says they are the same version.
+    /// # Arguments
+    /// * `other` - The version that would replace this one
+    pub fn change_to(&self, other: &ModVersion) -> VersionChange {
+        if !self.is_known() || !other.is_known() {
+            return VersionChange::Unknown;
+        }
+
+        match other.cmp(self) {
+            Ordering::Greater => VersionChange::Upgrade,
+            Ordering::Less => VersionChange::Downgrade,
+            Ordering::Equal => VersionChange::Identical,
+        }
+    }
+}
+
+impl Default for ModVersion {
+    fn default() -> Self {
+        ModVersion::unknown()
+    }
+}
+
+impl FromStr for ModVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("unknown") {
+            return Ok(ModVersion::unknown());
+        }
+
+        let numbers = trimmed.strip_prefix(|c| c == 'v' || c == 'V').unwrap_or(trimmed);
+        let parts: Vec<&str> = numbers.split('.').collect();
+        if parts.len() > COMPONENT_COUNT {
+            return Err(format!("Version {:?} has more than {} components", s, COMPONENT_COUNT));
+        }
+
+        let mut components = [0; COMPONENT_COUNT];
+        for (i, part) in parts.iter().enumerate() {
+            components[i] = part
+                .parse()
+                .map_err(|e| format!("Invalid component {:?} in version {:?}: {}", part, s, e))?;
+        }
+
+        Ok(ModVersion {
+            components: Some(components),
+            raw: trimmed.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ModVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+impl PartialEq for ModVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ModVersion {}
+
+impl PartialOrd for ModVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Unknown versions are ordered before every known version
+impl Ord for ModVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.components, &other.components) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl Serialize for ModVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.raw.as_str())
+    }
+}
+
+/// Versions that fail to parse are kept as unknown rather than failing the whole document
+impl<'de> Deserialize<'de> for ModVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Ok(raw.parse().unwrap_or(ModVersion {
+            components: None,
+            raw,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> ModVersion {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_full_short_and_prefixed_versions() {
+        assert_eq!(version("1.5.78.11833").components, Some([1, 5, 78, 11833]));
+        assert_eq!(version("1.2").components, Some([1, 2, 0, 0]));
+        assert_eq!(version("v1.2.3").components, Some([1, 2, 3, 0]));
+        assert_eq!(version("V2").to_string(), "V2");
+        assert!(!version("Unknown").is_known());
+        assert!("1.2.3.4.5".parse::<ModVersion>().is_err());
+        assert!("1.x".parse::<ModVersion>().is_err());
+    }
+
+    #[test]
+    fn unknown_sorts_lowest() {
+        let mut versions = [version("0.0.0.1"), ModVersion::unknown(), version("0")];
+        versions.sort();
+        assert!(!versions[0].is_known());
+        assert_eq!(versions[1], version("0"));
+        assert_eq!(version("1.2"), version("1.2.0.0"));
+    }
+
+    #[test]
+    fn classifies_version_changes() {
+        assert_eq!(
+            version("1.0").change_to(&version("1.1")),
+            VersionChange::Upgrade
+        );
+        assert_eq!(
+            version("1.1").change_to(&version("1.0.9")),
+            VersionChange::Downgrade
+        );
+        assert_eq!(
+            version("v1.1").change_to(&version("1.1.0.0")),
+            VersionChange::Identical
+        );
+        assert_eq!(
+            ModVersion::unknown().change_to(&version("1.0")),
+            VersionChange::Unknown
+        );
+        assert_eq!(
+            ModVersion::unknown().change_to(&ModVersion::unknown()),
+            VersionChange::Unknown
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use crate::error::Error;
+use aes::Aes256;
+use ecb::cipher::block_padding::Pkcs7;
+use ecb::cipher::{BlockDecryptMut, BlockEncryptMut, KeyInit};
+use serde_json::Value;
+
+/// The key the game encrypts its saves with
+const SAVE_KEY: &[u8; 32] = b"UKu52ePUBwetZ9wNX88o54dnfKRu0T1l";
+
+/// The header C#'s BinaryFormatter writes before serializing a single string
+const HEADER: [u8; 22] = [
+    0, 1, 0, 0, 0, 255, 255, 255, 255, 1, 0, 0, 0, 0, 0, 0, 0, 6, 1, 0, 0, 0,
+];
+
+/// The record BinaryFormatter writes after the serialized string
+const END_MARKER: u8 = 11;
+
+/// The keys every save's JSON contains at its top level
+const REQUIRED_KEYS: [&str; 2] = ["playerData", "sceneData"];
+
+/// Decrypt the contents of a .dat save file into its JSON
+/// # Arguments
+/// * `bytes` - The contents of the save file
+pub fn decode_save(bytes: &[u8]) -> Result<Value, Error> {
+    let rest = bytes.strip_prefix(&HEADER[..]).ok_or_else(|| {
+        Error::Parse("The file does not start with the expected save header.".to_string())
+    })?;
+    let (length, rest) = read_length(rest)?;
+    if rest.len() < length {
+        return Err(Error::Parse(
+            "The save ends before its data does.".to_string(),
+        ));
+    }
+
+    let encoded = &rest[..length];
+    let encrypted = base64::decode(encoded)
+        .map_err(|e| Error::Parse(format!("The save data is not valid Base64: {}", e)))?;
+    let decrypted = ecb::Decryptor::<Aes256>::new(SAVE_KEY.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(encrypted.as_slice())
+        .map_err(|_| Error::Parse("The save data could not be decrypted.".to_string()))?;
+    let json: Value = serde_json::from_slice(decrypted.as_slice())
+        .map_err(|e| Error::Parse(format!("The decrypted save data is not valid JSON: {}", e)))?;
+    validate_save_json(&json)?;
+    Ok(json)
+}
+
+/// Encrypt save JSON into the contents of a .dat save file the game can load
+/// # Arguments
+/// * `json` - The save's JSON, containing its player data and scene data
+pub fn encode_save(json: &Value) -> Result<Vec<u8>, Error> {
+    validate_save_json(json)?;
+    let plain = json.to_string();
+    let encrypted = ecb::Encryptor::<Aes256>::new(SAVE_KEY.into())
+        .encrypt_padded_vec_mut::<Pkcs7>(plain.as_bytes());
+    let encoded = base64::encode(encrypted);
+
+    let mut bytes = HEADER.to_vec();
+    write_length(&mut bytes, encoded.len());
+    bytes.extend_from_slice(encoded.as_bytes());
+    bytes.push(END_MARKER);
+    Ok(bytes)
+}
+
+/// Check that JSON has the shape of a save
+/// # Arguments
+/// * `json` - The JSON to be checked
+fn validate_save_json(json: &Value) -> Result<(), Error> {
+    for key in REQUIRED_KEYS {
+        if !json.get(key).map(Value::is_object).unwrap_or(false) {
+            return Err(Error::Parse(format!(
+                "The save JSON has no {:?} object.",
+                key
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the 7-bit encoded length that BinaryFormatter prefixes strings with
+/// # Arguments
+/// * `bytes` - The bytes starting with the length
+fn read_length(bytes: &[u8]) -> Result<(usize, &[u8]), Error> {
+    let mut length = 0;
+    for (i, byte) in bytes.iter().enumerate().take(5) {
+        length |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((length, &bytes[i + 1..]));
+        }
+    }
+
+    Err(Error::Parse(
+        "The length of the save data is invalid.".to_string(),
+    ))
+}
+
+/// Write a length in the 7-bit encoding that BinaryFormatter prefixes strings with
+/// # Arguments
+/// * `bytes` - The bytes to append the length to
+/// * `length` - The length to be written
+fn write_length(bytes: &mut Vec<u8>, mut length: usize) {
+    while length >= 0x80 {
+        bytes.push((length as u8 & 0x7f) | 0x80);
+        length >>= 7;
+    }
+    bytes.push(length as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A save file encrypted outside of Butterfly, whose data is long enough to need two bytes
+    /// for its length
+    const FIXTURE: &str = "AAEAAAD/////AQAAAAAAAAAGAQAAAKwBVEZlSXJUWkRRS3NpSDU0YkNMa0ZMY0FBU2xUbFhPZVVwMVFCaGIvNEpMM3NKUTlud3c5a0l1QzM2VE5uc0FPSU9ORXhnSVl6c1IyYng2QThOL21Jemk0cS92UXA4TW1obmcyYWlQc1JwU2NucEtaL2ROVVRueXUyL2grNjBoZkdrT3l3R2pyYmluaFRGTDJXMkI1YkhtZWFPVGE0aE9ocGdRNG5CSlltcWVJPQs=";
+
+    fn fixture_json() -> Value {
+        json!({
+            "playerData": {
+                "geo": 1234,
+                "health": 5,
+                "maxHealth": 9,
+                "playTime": 3600.5,
+                "version": "1.5.78.11833"
+            },
+            "sceneData": { "geoRocks": [] }
+        })
+    }
+
+    #[test]
+    fn decodes_known_save() {
+        let bytes = base64::decode(FIXTURE).unwrap();
+        assert_eq!(decode_save(bytes.as_slice()).unwrap(), fixture_json());
+        assert_eq!(encode_save(&fixture_json()).unwrap(), bytes);
+    }
+
+    #[test]
+    fn round_trips_save() {
+        let json = json!({
+            "playerData": { "name": "x".repeat(20000) },
+            "sceneData": {}
+        });
+        let bytes = encode_save(&json).unwrap();
+        assert_eq!(decode_save(bytes.as_slice()).unwrap(), json);
+        assert!(decode_save(&bytes[1..]).is_err());
+        assert!(encode_save(&json!({ "playerData": {} })).is_err());
+    }
+}
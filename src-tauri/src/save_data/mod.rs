@@ -0,0 +1,2 @@
+pub mod codec;
+pub mod slots;
\ No newline at end of file
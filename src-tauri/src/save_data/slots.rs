@@ -0,0 +1,102 @@
+use crate::error::Error;
+use crate::save_data::codec::decode_save;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// The save slots the game offers
+pub const SAVE_SLOTS: [u8; 4] = [1, 2, 3, 4];
+
+/// A summary of the save in a save slot, as shown on the game's save selection screen
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SaveSlot {
+    #[serde(rename = "Slot")]
+    pub slot: u8,
+    /// Whether the slot has a save in it
+    #[serde(rename = "Exists")]
+    pub exists: bool,
+    /// The time the save was last written, in seconds since the Unix epoch
+    #[serde(rename = "Modified At")]
+    pub modified_at: Option<u64>,
+    /// The time played, in seconds
+    #[serde(rename = "Play Time")]
+    pub play_time: Option<f64>,
+    #[serde(rename = "Completion")]
+    pub completion: Option<f64>,
+    #[serde(rename = "Geo")]
+    pub geo: Option<i64>,
+    /// The map zone the player was last in, as recorded by the game
+    #[serde(rename = "Area")]
+    pub area: Option<String>,
+    /// The scene the player respawns in when loading the save
+    #[serde(rename = "Respawn Scene")]
+    pub respawn_scene: Option<String>,
+    #[serde(rename = "Steel Soul")]
+    pub steel_soul: Option<bool>,
+    /// The version of the game that last wrote the save
+    #[serde(rename = "Game Version")]
+    pub game_version: Option<String>,
+    /// Why the save could not be read, if it exists but is unreadable
+    #[serde(rename = "Error")]
+    pub error: Option<Error>,
+}
+
+/// Summarize the save in a save slot
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves from
+/// * `slot` - The number of the save slot
+pub fn read_slot(saves_dir: &Path, slot: u8) -> SaveSlot {
+    let mut summary = SaveSlot {
+        slot,
+        ..Default::default()
+    };
+    let save_path = saves_dir.join(format!("user{}.dat", slot));
+    if !save_path.is_file() {
+        return summary;
+    }
+
+    summary.exists = true;
+    summary.modified_at = fs::metadata(save_path.as_path())
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs());
+
+    let json = match fs::read(save_path.as_path())
+        .map_err(Error::from)
+        .and_then(|bytes| decode_save(bytes.as_slice()))
+    {
+        Ok(json) => json,
+        Err(e) => {
+            summary.error = Some(e);
+            return summary;
+        }
+    };
+
+    let player_data = &json["playerData"];
+    summary.play_time = player_data["playTime"].as_f64();
+    summary.completion = player_data["completionPercentage"].as_f64();
+    summary.geo = player_data["geo"].as_i64();
+    summary.area = match &player_data["mapZone"] {
+        Value::String(zone) => Some(zone.clone()),
+        Value::Number(zone) => Some(zone.to_string()),
+        _ => None,
+    };
+    summary.respawn_scene = player_data["respawnScene"].as_str().map(String::from);
+    // Steel Soul saves record 1 while alive and 2 once the run has ended in death
+    summary.steel_soul = player_data["permadeathMode"].as_i64().map(|mode| mode != 0);
+    summary.game_version = player_data["version"].as_str().map(String::from);
+    summary
+}
+
+/// Summarize the saves in every save slot
+/// # Arguments
+/// * `saves_dir` - The folder the game reads saves from
+pub fn list_slots(saves_dir: &Path) -> Vec<SaveSlot> {
+    SAVE_SLOTS
+        .iter()
+        .map(|slot| read_slot(saves_dir, *slot))
+        .collect()
+}
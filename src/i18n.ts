@@ -4,6 +4,7 @@ const messages = {
     en: {
         message: {
             all: "All",
+            apiLinksStale: "Could not reach ApiLinks, installed the Modding API from the last ApiLinks that was fetched.",
             cancel: "Cancel",
             chooseSaveSlot: "Choose save slot number:",
             createNewProfile: "Create New Profile",
@@ -21,24 +22,33 @@ const messages = {
             exportProfiles: "Export Profiles",
             importProfiles: "Import Profiles",
             importSave: "Import Save",
+            importShareCode: "Import Share Code",
             install: "Install",
             installed: "Installed",
             languages: "Languages",
             manualInstall: "Manual Install",
+            modListStale: "Could not reach ModLinks, showing the last mod list that was fetched.",
             modReadMe: "ReadMe",
             name: "Name",
             newMod: "New!",
             openMods: "Open Mods",
+            overwriteSavePrompt: "This save slot already has a save in it. Overwrite it? A backup will be kept.",
+            pauseDownloads: "Pause Downloads",
             profileNamePlaceholder: "Enter profile name here",
             profileNamePrompt: "Give your profile a name",
             profiles: "Profiles",
+            queued: "Queued",
             readMe: "Read me",
             report: "Report a bug/suggest a feature",
             repository: "Repository",
+            removedMods: "No longer listed on ModLinks:",
             reset: "Reset",
+            resumeDownloads: "Resume Downloads",
             searchMods: "Search Mods",
             selectMods: "Select Mods",
             selectTags: "Select Tags",
+            share: "Share",
+            shareCodePrompt: "Paste a profile share code",
             source: "Source code",
             tags: "Tags",
             uninstall: "Uninstall",
@@ -270,6 +280,7 @@ const messages = {
 const i18n = createI18n({
     legacy: false,
     locale: "en",
+    fallbackLocale: "en",
     globalInjection: true,
     messages: messages,
 })